@@ -16,8 +16,9 @@ export default class CreateStakingContract extends Base {
 
   static flags = {
     stakingContractCodeId: Flags.integer({char: 'c', description: "Staking contract codeID", required: true}),
-    denom: Flags.string({char: 'd', description: "Denom unit of the staking token", required: true}),
-    tokenExponent: Flags.integer({char: 'e', description: "Exponent of the staking token", required: true}),
+    denom: Flags.string({char: 'd', description: "Denom unit of the staking token", exactlyOne: ['denom', 'cw20Address']}),
+    tokenExponent: Flags.integer({char: 'e', description: "Exponent of the staking token", dependsOn: ['denom']}),
+    cw20Address: Flags.string({char: 't', description: "Address of the CW20 staking token", exactlyOne: ['denom', 'cw20Address']}),
     unbondingPeriod: Flags.integer({char: 'u', description: "Unbounding period in seconds", required: false}),
   }
 
@@ -30,11 +31,9 @@ export default class CreateStakingContract extends Base {
       .connectWithSigner(this.chainConfig!.rpcEndpoint, wallet, {"gasPrice": this.gasPrice});
     const createStakingContractMsg: CreateStakingContractMsg = {
       code_id: flags.stakingContractCodeId,
-      denom_unit: {
-        denom: flags.denom,
-        exponent: flags.tokenExponent,
-        aliases: [],
-      },
+      token: flags.cw20Address
+        ? { cw20: { address: flags.cw20Address } }
+        : { native: { denom: flags.denom!, exponent: flags.tokenExponent ?? 0, aliases: [] } },
       unbonding_period: undefined,
       owner: signerAddress,
     };
@@ -42,11 +41,13 @@ export default class CreateStakingContract extends Base {
       "create_staking_contract": createStakingContractMsg,
     }
 
-    const executeResult = await client.execute(signerAddress, args.contractAddress, executeMsg, "auto", `Symphony Native Staking Contract ${flags.denom}`, []);
+    // CW20 staking contracts are registered under the token address
+    const denom = flags.cw20Address ?? flags.denom!;
+    const executeResult = await client.execute(signerAddress, args.contractAddress, executeMsg, "auto", `Symphony Staking Contract ${denom}`, []);
     this.log("Transaction Hash: ", executeResult.transactionHash);
     const queryMsg = {
       "staking_contract_by_denom": {
-        denom: flags.denom,
+        denom,
       } as QueryStakingContractByDenom
     }
     const stakingContractResult: StakingContractByDenomResponse = await client.queryContractSmart(args.contractAddress, queryMsg);
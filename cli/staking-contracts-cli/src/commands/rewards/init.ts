@@ -31,11 +31,11 @@ export default class Initialize extends Base {
     const client = await SigningCosmWasmClient
       .connectWithSigner(this.chainConfig!.rpcEndpoint, wallet, {"gasPrice": this.gasPrice});
     const initMsg: InstantiateMsg = {
-      reward_token: {
+      reward_tokens: [{
         denom: flags.rewardsDenom,
         exponent: flags.rewardsExponent,
         aliases: [],
-      },
+      }],
       rewards_distribution: rewardsDistribution,
       staking_orchestrator_addr: flags.orchestratorAddr,
     };
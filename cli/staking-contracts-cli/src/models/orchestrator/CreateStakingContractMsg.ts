@@ -1,9 +1,13 @@
 import { DenomUnit } from '../DenomUnit.js';
 import { Duration } from '../Duration.js';
 
+export type StakingToken =
+  | { native: DenomUnit }
+  | { cw20: { address: string } };
+
 export interface CreateStakingContractMsg {
   code_id: number;
-  denom_unit: DenomUnit;
+  token: StakingToken;
   unbonding_period?: Duration;
   owner?: string;
-}
\ No newline at end of file
+}
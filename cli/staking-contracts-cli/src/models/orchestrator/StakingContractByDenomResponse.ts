@@ -8,4 +8,10 @@ export interface StakingContractByDenomResponse {
 export interface RegisteredContract {
   address: string,
   token: DenomUnit,
+  kind: 'native' | 'cw20',
+  // nanoseconds since the epoch
+  registered_at: string,
+  status: 'active' | 'deprecated' | 'closed',
+  code_id?: number,
+  version?: string,
 }
\ No newline at end of file
@@ -5,9 +5,12 @@ export interface RewardsDistribution {
   weight: number;
 }
 
+export type EmptyPoolPolicy = 'queue' | 'redistribute' | 'return_to_sender';
+
 export interface InstantiateMsg {
   owner?: string;
   staking_orchestrator_addr: string;
-  reward_token: DenomUnit;
+  reward_tokens: DenomUnit[];
   rewards_distribution: RewardsDistribution[];
+  empty_pool_policy?: EmptyPoolPolicy;
 }
\ No newline at end of file
@@ -0,0 +1,490 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::migrations::MIGRATIONS;
+use crate::msg::MigrateMsg;
+use crate::state::{Config, BALANCE, CLAIMS, CONFIG, HOOKS, MAX_CLAIMS, STAKED_BALANCES, STAKED_TOTAL};
+use cosmwasm_std::{from_json, to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, DenomUnit, Empty, Env, MessageInfo, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg};
+use cw2::set_contract_version;
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, TokenInfoResponse};
+use cw_ownable::get_ownership;
+use cw_storage_plus::Bound;
+use cw_utils::Duration;
+use symphony_interfaces::cw20_staking::{ExecuteMsg, InstantiateMsg, ReceiveMsg};
+use symphony_interfaces::orchestrator::TokenKind;
+use symphony_interfaces::staking::{ApprovalsResponse, ConfigResponse, InfoResponse, ListStakersResponse, LockTiersResponse, PositionsResponse, QueryMsg, RebondOrder, ReconcileResponse, SlashEventsResponse, StakeChangedHookMsg, StakedBalanceAtHeightResponse, StakerBalanceResponse, TotalPowerAtHeightResponse, TotalStakedAtHeightResponse, VotingPowerAtHeightResponse};
+use symphony_utils::duration::validate_duration;
+use symphony_utils::migrate::migrate_contract;
+
+pub(crate) const CONTRACT_NAME: &str = "crates.io:symphony-cw20-staking";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response<Empty>, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let owner = msg.owner.as_deref().unwrap_or(info.sender.as_str());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(owner))?;
+
+    validate_duration(msg.unbonding_period)?;
+
+    let token_address = deps.api.addr_validate(&msg.token_address)?;
+    let token_info: TokenInfoResponse = deps.querier.query_wasm_smart(&token_address, &Cw20QueryMsg::TokenInfo {})?;
+
+    let config = Config {
+        token_address: token_address.clone(),
+        staking_token: DenomUnit {
+            denom: token_address.to_string(),
+            exponent: token_info.decimals as u32,
+            aliases: vec![token_info.symbol],
+        },
+        unstaking_duration: msg.unbonding_period,
+        dao: msg.dao.as_deref().map(|dao| deps.api.addr_validate(dao)).transpose()?,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+
+    for hook in msg.hooks.unwrap_or_default() {
+        HOOKS.add_hook(deps.storage, deps.api.addr_validate(&hook)?)?;
+    }
+
+    STAKED_TOTAL.save(deps.storage, &Uint128::zero(), env.block.height)?;
+    BALANCE.save(deps.storage, &Uint128::zero())?;
+    Ok(
+        Response::new()
+            .add_attribute("action", "instantiate")
+            .add_attribute("owner", owner)
+            .add_attribute("token_address", token_address)
+            .add_attribute("token_exponent", config.staking_token.exponent.to_string())
+    )
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut,
+               env: Env,
+               info: MessageInfo,
+               msg: ExecuteMsg) -> Result<Response<Empty>, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateOwnership(action) => execute_update_owner(deps, info, env, action),
+        ExecuteMsg::UpdateConfig { unbonding_period } => execute_update_config(deps, info, unbonding_period),
+        ExecuteMsg::UpdateSettings { dao } => execute_update_settings(deps, info, dao),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::Unstake { amount } => execute_unstake(deps, env, info, amount),
+        ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+        ExecuteMsg::SweepSurplus { recipient } => execute_sweep_surplus(deps, env, info, recipient),
+        ExecuteMsg::AddHook { addr } => execute_add_hook(deps, info, addr),
+        ExecuteMsg::RemoveHook { addr } => execute_remove_hook(deps, info, addr),
+    }
+}
+
+pub fn execute_update_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    action: cw_ownable::Action,
+) -> Result<Response<Empty>, ContractError> {
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+    Ok(Response::default().add_attributes(ownership.into_attributes()))
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    unbonding_period: Option<Duration>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    validate_duration(unbonding_period)?;
+
+    CONFIG.update(deps.storage, |mut config| -> Result<Config, StdError> {
+        config.unstaking_duration = unbonding_period;
+        Ok(config)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute(
+            "unstaking_duration",
+            unbonding_period
+                .map(|d| format!("{d}"))
+                .unwrap_or_else(|| "none".to_string()),
+        ))
+}
+
+pub fn execute_update_settings(
+    deps: DepsMut,
+    info: MessageInfo,
+    dao: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let dao = dao.map(|dao| deps.api.addr_validate(&dao)).transpose()?;
+
+    let config = CONFIG.update(deps.storage, |mut config| -> Result<Config, StdError> {
+        if dao.is_some() {
+            config.dao = dao;
+        }
+        Ok(config)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_settings")
+        .add_attribute("dao", config.dao.map(|dao| dao.to_string()).unwrap_or_else(|| "none".to_string())))
+}
+
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.token_address {
+        return Err(ContractError::InvalidToken {});
+    }
+    if msg.amount.is_zero() {
+        return Err(ContractError::NoStakeAmount {});
+    }
+
+    let sender = deps.api.addr_validate(&msg.sender)?;
+    match from_json(&msg.msg)? {
+        ReceiveMsg::Stake {} => execute_stake(deps, env, &config, sender, msg.amount),
+    }
+}
+
+fn execute_stake(
+    deps: DepsMut,
+    env: Env,
+    config: &Config,
+    sender: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let new_balance = STAKED_BALANCES.update(
+        deps.storage,
+        &sender,
+        env.block.height,
+        |bal| -> StdResult<Uint128> { Ok(bal.unwrap_or_default().checked_add(amount)?) },
+    )?;
+    STAKED_TOTAL.update(
+        deps.storage,
+        env.block.height,
+        |total| -> StdResult<Uint128> {
+            // Initialized during instantiate - OK to unwrap.
+            Ok(total.unwrap().checked_add(amount)?)
+        },
+    )?;
+    BALANCE.update(deps.storage, |balance| -> StdResult<_> { Ok(balance.checked_add(amount)?) })?;
+
+    let hook_msgs = stake_changed_hook_msgs(
+        deps.storage,
+        &sender,
+        new_balance.checked_sub(amount).map_err(StdError::from)?,
+        new_balance,
+        &config.staking_token.denom,
+    )?;
+
+    Ok(Response::new()
+        .add_submessages(hook_msgs)
+        .add_attribute("action", "stake")
+        .add_attribute("from", sender)
+        .add_attribute("denom", config.staking_token.denom.as_str())
+        .add_attribute("amount", amount))
+}
+
+fn transfer_msg(config: &Config, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: config.token_address.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }.into())
+}
+
+pub fn execute_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let user_balance = STAKED_BALANCES.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NoUnstakeAmount {})?;
+
+    if amount.is_zero() || amount > user_balance {
+        return Err(ContractError::InvalidUnstakeAmount {});
+    }
+
+    let new_balance = STAKED_BALANCES.update(
+        deps.storage,
+        &info.sender,
+        env.block.height,
+        |bal| -> StdResult<Uint128> { Ok(bal.unwrap_or_default().checked_sub(amount)?) },
+    )?;
+    STAKED_TOTAL.update(
+        deps.storage,
+        env.block.height,
+        |total| -> StdResult<Uint128> {
+            // Initialized during instantiate - OK to unwrap.
+            Ok(total.unwrap().checked_sub(amount)?)
+        },
+    )?;
+
+    let hook_msgs = stake_changed_hook_msgs(
+        deps.storage,
+        &info.sender,
+        user_balance,
+        new_balance,
+        &config.staking_token.denom,
+    )?;
+
+    let response = Response::new()
+        .add_submessages(hook_msgs)
+        .add_attribute("action", "unstake")
+        .add_attribute("from", info.sender.as_str())
+        .add_attribute("denom", config.staking_token.denom.as_str())
+        .add_attribute("amount", amount);
+
+    match config.unstaking_duration {
+        None => {
+            BALANCE.update(deps.storage, |balance| -> StdResult<_> { Ok(balance.checked_sub(amount)?) })?;
+            Ok(response.add_message(transfer_msg(&config, &info.sender, amount)?))
+        }
+        Some(duration) => {
+            let pending_claims = CLAIMS.query_claims(deps.as_ref(), &info.sender)?.claims;
+            if pending_claims.len() >= MAX_CLAIMS as usize {
+                return Err(ContractError::TooManyClaims {});
+            }
+
+            CLAIMS.create_claim(deps.storage, &info.sender, amount, duration.after(&env.block))?;
+            Ok(response.add_attribute("claim_duration", format!("{duration}")))
+        }
+    }
+}
+
+pub fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mature_claims = CLAIMS.claim_tokens(deps.storage, &info.sender, &env.block, None)?;
+    if mature_claims.is_zero() {
+        return Err(ContractError::NothingToClaim {})
+    }
+    BALANCE.update(deps.storage, |balance| -> StdResult<_> { Ok(balance.checked_sub(mature_claims)?) })?;
+
+    let config = CONFIG.load(deps.storage)?;
+    Ok(Response::new()
+        .add_message(transfer_msg(&config, &info.sender, mature_claims)?)
+        .add_attribute("action", "claim")
+        .add_attribute("from", info.sender)
+        .add_attribute("denom", config.staking_token.denom)
+        .add_attribute("amount", mature_claims))
+}
+
+pub fn execute_sweep_surplus(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let reconciliation = reconcile(deps.as_ref(), &env)?;
+    let mut msgs = vec![];
+    if !reconciliation.surplus.is_zero() {
+        msgs.push(transfer_msg(&config, &recipient, reconciliation.surplus)?);
+    }
+    if !reconciliation.other_balances.is_empty() {
+        msgs.push(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: reconciliation.other_balances,
+        }.into());
+    }
+
+    if msgs.is_empty() {
+        return Err(ContractError::NothingToSweep {});
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "sweep_surplus")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", reconciliation.surplus))
+}
+
+pub fn execute_add_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let hook = deps.api.addr_validate(&addr)?;
+    HOOKS.add_hook(deps.storage, hook)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_hook")
+        .add_attribute("hook", addr))
+}
+
+pub fn execute_remove_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let hook = deps.api.addr_validate(&addr)?;
+    HOOKS.remove_hook(deps.storage, hook)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_hook")
+        .add_attribute("hook", addr))
+}
+
+fn stake_changed_hook_msgs(
+    storage: &dyn Storage,
+    addr: &Addr,
+    old_balance: Uint128,
+    new_balance: Uint128,
+    denom: &str,
+) -> StdResult<Vec<SubMsg>> {
+    let msg = StakeChangedHookMsg {
+        addr: addr.to_string(),
+        old_balance,
+        new_balance,
+        denom: denom.to_string(),
+    };
+
+    HOOKS.prepare_hooks(storage, |hook| {
+        msg.clone().into_cosmos_msg(hook).map(SubMsg::new)
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response<Empty>, ContractError> {
+    let from_version = migrate_contract(deps.storage, &env, CONTRACT_NAME, CONTRACT_VERSION, MIGRATIONS)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+/// Lock tiers, positions, slashing, delegation and operator approvals are native staking
+/// features, so those queries answer with empty results.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Ownership {} => to_json_binary(&get_ownership(deps.storage)?),
+        QueryMsg::StakedBalanceAtHeight { address, height } => to_json_binary(&query_staked_balance(deps, env, address, height)?),
+        QueryMsg::TotalStakedAtHeight { height } => to_json_binary(&query_total_staked_at_height(deps, env, height)?),
+        QueryMsg::Claims { address } => to_json_binary(&CLAIMS.query_claims(deps, &deps.api.addr_validate(&address)?)?),
+        QueryMsg::ListStakers { start_after, limit } => {
+            to_json_binary(&query_all_stakers(deps, start_after, limit)?)
+        }
+        QueryMsg::Hooks {} => to_json_binary(&HOOKS.query_hooks(deps)?),
+        QueryMsg::LockTiers {} => to_json_binary(&LockTiersResponse { lock_tiers: vec![] }),
+        QueryMsg::Positions { address } => {
+            deps.api.addr_validate(&address)?;
+            to_json_binary(&PositionsResponse { positions: vec![] })
+        }
+        QueryMsg::VotingPowerAtHeight { address, height } => {
+            let staked = query_staked_balance(deps, env, address, height)?;
+            to_json_binary(&VotingPowerAtHeightResponse { power: staked.balance, height: staked.height })
+        }
+        QueryMsg::TotalPowerAtHeight { height } => {
+            let total = query_total_staked_at_height(deps, env, height)?;
+            to_json_binary(&TotalPowerAtHeightResponse { power: total.total, height: total.height })
+        }
+        QueryMsg::Dao {} => to_json_binary(&CONFIG.load(deps.storage)?.dao.ok_or_else(|| StdError::not_found("dao"))?),
+        QueryMsg::Info {} => to_json_binary(&InfoResponse { info: cw2::get_contract_version(deps.storage)? }),
+        QueryMsg::SlashEvents { .. } => to_json_binary(&SlashEventsResponse { slash_events: vec![] }),
+        QueryMsg::Reconcile {} => to_json_binary(&reconcile(deps, &env)?),
+        QueryMsg::Approvals { owner, .. } => {
+            deps.api.addr_validate(&owner)?;
+            to_json_binary(&ApprovalsResponse { approvals: vec![] })
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        staking_token: config.staking_token,
+        unstaking_duration: config.unstaking_duration,
+        rebond_order: RebondOrder::default(),
+        lock_tiers: vec![],
+        dao: config.dao,
+        delegation: None,
+        token_kind: TokenKind::Cw20,
+    })
+}
+
+pub fn query_staked_balance(deps: Deps, env: Env, address: String, height: Option<u64>) -> StdResult<StakedBalanceAtHeightResponse> {
+    let query_address = deps.api.addr_validate(&address)?;
+    let query_height = height.unwrap_or(env.block.height);
+    let balance = STAKED_BALANCES.may_load_at_height(deps.storage, &query_address, query_height)?.unwrap_or_default();
+    Ok(StakedBalanceAtHeightResponse { balance, height: query_height })
+}
+
+pub fn query_total_staked_at_height(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalStakedAtHeightResponse> {
+    let height = height.unwrap_or(env.block.height);
+    let total = STAKED_TOTAL
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default();
+    Ok(TotalStakedAtHeightResponse { total, height })
+}
+
+pub fn query_all_stakers(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<ListStakersResponse> {
+    let start_addr = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let stakers = STAKED_BALANCES
+        .range(deps.storage, start_addr.as_ref().map(Bound::exclusive), None, cosmwasm_std::Order::Ascending)
+        .take(limit.map(|limit| limit as usize).unwrap_or(usize::MAX))
+        .map(|item| item.map(|(addr, balance)| StakerBalanceResponse {
+            address: addr.to_string(),
+            balance,
+        }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListStakersResponse { stakers })
+}
+
+#[allow(deprecated)]
+fn reconcile(deps: Deps, env: &Env) -> StdResult<ReconcileResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let ledger = BALANCE.load(deps.storage)?;
+
+    let balance: BalanceResponse = deps.querier.query_wasm_smart(&config.token_address, &Cw20QueryMsg::Balance {
+        address: env.contract.address.to_string(),
+    })?;
+    let other_balances = deps.querier.query_all_balances(&env.contract.address)?;
+
+    Ok(ReconcileResponse {
+        denom: config.staking_token.denom,
+        ledger,
+        balance: balance.balance,
+        delegated: Uint128::zero(),
+        surplus: balance.balance.saturating_sub(ledger),
+        deficit: ledger.saturating_sub(balance.balance),
+        other_balances,
+        unforwarded_rewards: vec![],
+    })
+}
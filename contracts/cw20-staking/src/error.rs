@@ -0,0 +1,44 @@
+use cosmwasm_std::StdError;
+use cw_controllers::HookError;
+use symphony_utils::duration::UnboundingDurationError;
+use symphony_utils::migrate::MigrationError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    UnboundingDurationError(#[from] UnboundingDurationError),
+
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+
+    #[error("No stake amount")]
+    NoStakeAmount {},
+
+    #[error("Only the staking token can be received")]
+    InvalidToken {},
+
+    #[error("No stake amount to unstake")]
+    NoUnstakeAmount {},
+
+    #[error("Invalid amount to unstake")]
+    InvalidUnstakeAmount {},
+
+    #[error("Too many pending claims")]
+    TooManyClaims {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("No surplus to sweep")]
+    NothingToSweep {},
+
+    #[error(transparent)]
+    Ownership(#[from] cw_ownable::OwnershipError),
+
+    #[error(transparent)]
+    Hook(#[from] HookError),
+}
@@ -0,0 +1,8 @@
+pub mod state;
+pub mod msg;
+pub mod contract;
+pub mod error;
+pub mod migrations;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,5 @@
+use crate::error::ContractError;
+use symphony_utils::migrate::Migration;
+
+/// State transformers, by the version they migrate to.
+pub const MIGRATIONS: &[Migration<ContractError>] = &[];
@@ -0,0 +1,5 @@
+use cosmwasm_schema::cw_serde;
+
+
+#[cw_serde]
+pub struct MigrateMsg {}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, DenomUnit, Uint128};
+use cw_controllers::{Claims, Hooks};
+use cw_storage_plus::{Item, SnapshotItem, SnapshotMap, Strategy};
+use cw_utils::Duration;
+
+
+#[cw_serde]
+pub struct Config {
+    pub token_address: Addr,
+    /// Denom is the token address, exponent its decimals.
+    pub staking_token: DenomUnit,
+    pub unstaking_duration: Option<Duration>,
+    /// DAO the contract serves as voting module for.
+    pub dao: Option<Addr>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub const STAKED_BALANCES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "staked_balances",
+    "staked_balance__checkpoints",
+    "staked_balance__changelog",
+    Strategy::EveryBlock,
+);
+
+pub const STAKED_TOTAL: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_staked",
+    "total_staked__checkpoints",
+    "total_staked__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Staked and unbonding tokens owed to stakers.
+pub const BALANCE: Item<Uint128> = Item::new("balance");
+
+pub const MAX_CLAIMS: u64 = 100;
+
+pub const CLAIMS: Claims = Claims::new("claims");
+
+pub const HOOKS: Hooks = Hooks::new("hooks");
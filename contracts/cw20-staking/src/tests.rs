@@ -0,0 +1,218 @@
+use crate::error::ContractError;
+use cosmwasm_std::{to_json_binary, Addr, BlockInfo, Empty, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_controllers::ClaimsResponse;
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use cw_ownable::OwnershipError;
+use cw_utils::{Duration, Expiration};
+use symphony_interfaces::cw20_staking::{ExecuteMsg, InstantiateMsg, ReceiveMsg};
+use symphony_interfaces::staking::{ConfigResponse, QueryMsg, ReconcileResponse, StakedBalanceAtHeightResponse, TotalStakedAtHeightResponse};
+
+const OWNER: &str = "owner";
+const TIME_BETWEEN_BLOCKS: u64 = 5;
+
+fn mock_app() -> App {
+    App::default()
+}
+
+fn next_block(app: &mut App) {
+    app.set_block(BlockInfo {
+        height: app.block_info().height + 1,
+        time: app.block_info().time.plus_seconds(TIME_BETWEEN_BLOCKS),
+        chain_id: app.block_info().chain_id,
+    });
+}
+
+pub fn cw20_staking_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        crate::contract::execute,
+        crate::contract::instantiate,
+        crate::contract::query,
+    )
+        .with_migrate(crate::contract::migrate);
+    Box::new(contract)
+}
+
+pub fn cw20_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+fn instantiate_cw20(app: &mut App, balances: Vec<(Addr, u128)>) -> Addr {
+    let code_id = app.store_code(cw20_contract());
+    let msg = cw20_base::msg::InstantiateMsg {
+        name: "Staking Token".to_string(),
+        symbol: "STAKE".to_string(),
+        decimals: 6,
+        initial_balances: balances.into_iter()
+            .map(|(address, amount)| Cw20Coin { address: address.to_string(), amount: Uint128::new(amount) })
+            .collect(),
+        mint: None,
+        marketing: None,
+    };
+    app.instantiate_contract(code_id, app.api().addr_make(OWNER), &msg, &[], "cw20", None)
+        .unwrap()
+}
+
+fn instantiate_staking(app: &mut App, token: &Addr, unbonding_period: Option<Duration>) -> Addr {
+    let staking_code_id = app.store_code(cw20_staking_contract());
+    let msg = InstantiateMsg {
+        owner: None,
+        token_address: token.to_string(),
+        unbonding_period,
+        hooks: None,
+        dao: None,
+    };
+    app.instantiate_contract(
+        staking_code_id,
+        app.api().addr_make(OWNER),
+        &msg,
+        &[],
+        "staking",
+        Some(app.api().addr_make("admin").into()),
+    )
+        .unwrap()
+}
+
+fn stake(app: &mut App, token: &Addr, staking_contract: &Addr, sender: &Addr, amount: u128) -> AnyResult<()> {
+    app.execute_contract(sender.clone(), token.clone(), &Cw20ExecuteMsg::Send {
+        contract: staking_contract.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_json_binary(&ReceiveMsg::Stake {}).unwrap(),
+    }, &[]).map(|_| ())
+}
+
+fn cw20_balance(app: &App, token: &Addr, address: &Addr) -> Uint128 {
+    let balance: BalanceResponse = app.wrap().query_wasm_smart(token, &Cw20QueryMsg::Balance {
+        address: address.to_string(),
+    }).unwrap();
+    balance.balance
+}
+
+#[test]
+pub fn cw20_staking_instantiate() {
+    let app = &mut mock_app();
+    let token = instantiate_cw20(app, vec![]);
+    let staking_contract = instantiate_staking(app, &token, None);
+
+    let config: ConfigResponse = app.wrap().query_wasm_smart(staking_contract, &QueryMsg::Config {}).unwrap();
+    assert_eq!(config.staking_token.denom, token.to_string());
+    assert_eq!(config.staking_token.exponent, 6);
+    assert_eq!(config.staking_token.aliases, vec!["STAKE".to_string()]);
+    assert_eq!(config.unstaking_duration, None);
+}
+
+#[test]
+pub fn stake_should_only_accept_the_staking_token() {
+    let app = &mut mock_app();
+    let staker = app.api().addr_make("staker");
+    let token = instantiate_cw20(app, vec![(staker.clone(), 1_000)]);
+    let other_token = instantiate_cw20(app, vec![(staker.clone(), 1_000)]);
+    let staking_contract = instantiate_staking(app, &token, None);
+
+    let err = stake(app, &other_token, &staking_contract, &staker, 100).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidToken {});
+
+    stake(app, &token, &staking_contract, &staker, 100).unwrap();
+    next_block(app);
+
+    let staked: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
+        address: staker.to_string(),
+        height: None,
+    }).unwrap();
+    assert_eq!(staked.balance, Uint128::new(100));
+    let total: TotalStakedAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::TotalStakedAtHeight {
+        height: None,
+    }).unwrap();
+    assert_eq!(total.total, Uint128::new(100));
+    assert_eq!(cw20_balance(app, &token, &staker), Uint128::new(900));
+    assert_eq!(cw20_balance(app, &token, &staking_contract), Uint128::new(100));
+}
+
+#[test]
+pub fn unstake_with_unbonding_period_should_pay_out_on_claim() {
+    let app = &mut mock_app();
+    let staker = app.api().addr_make("staker");
+    let token = instantiate_cw20(app, vec![(staker.clone(), 1_000)]);
+    let staking_contract = instantiate_staking(app, &token, Some(Duration::Time(5)));
+
+    stake(app, &token, &staking_contract, &staker, 100).unwrap();
+    next_block(app);
+
+    let err = app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(101),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidUnstakeAmount {});
+
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(100),
+    }, &[]).unwrap();
+
+    let claims: ClaimsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Claims {
+        address: staker.to_string(),
+    }).unwrap();
+    assert_eq!(claims.claims.len(), 1);
+    assert_eq!(claims.claims[0].release_at, Expiration::AtTime(app.block_info().time.plus_seconds(5)));
+
+    let err = app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Claim {}, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToClaim {});
+
+    next_block(app);
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Claim {}, &[]).unwrap();
+    assert_eq!(cw20_balance(app, &token, &staker), Uint128::new(1_000));
+    assert_eq!(cw20_balance(app, &token, &staking_contract), Uint128::zero());
+}
+
+#[test]
+pub fn sweep_surplus_should_never_touch_staked_funds() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let staker = app.api().addr_make("staker");
+    let treasury = app.api().addr_make("treasury");
+    let token = instantiate_cw20(app, vec![(staker.clone(), 1_000)]);
+    let staking_contract = instantiate_staking(app, &token, None);
+
+    stake(app, &token, &staking_contract, &staker, 900).unwrap();
+    // accidental transfer
+    app.execute_contract(staker.clone(), token.clone(), &Cw20ExecuteMsg::Transfer {
+        recipient: staking_contract.to_string(),
+        amount: Uint128::new(50),
+    }, &[]).unwrap();
+
+    let reconcile: ReconcileResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Reconcile {}).unwrap();
+    assert_eq!(reconcile, ReconcileResponse {
+        denom: token.to_string(),
+        ledger: Uint128::new(900),
+        balance: Uint128::new(950),
+        delegated: Uint128::zero(),
+        surplus: Uint128::new(50),
+        deficit: Uint128::zero(),
+        other_balances: vec![],
+        unforwarded_rewards: vec![],
+    });
+
+    let err = app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::SweepSurplus {
+        recipient: staker.to_string(),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Ownership(OwnershipError::NotOwner));
+
+    app.execute_contract(owner.clone(), staking_contract.clone(), &ExecuteMsg::SweepSurplus {
+        recipient: treasury.to_string(),
+    }, &[]).unwrap();
+    assert_eq!(cw20_balance(app, &token, &treasury), Uint128::new(50));
+
+    let err = app.execute_contract(owner, staking_contract.clone(), &ExecuteMsg::SweepSurplus {
+        recipient: treasury.to_string(),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToSweep {});
+
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(900),
+    }, &[]).unwrap();
+    assert_eq!(cw20_balance(app, &token, &staker), Uint128::new(950));
+}
@@ -2,19 +2,25 @@
 use cosmwasm_std::entry_point;
 
 use crate::error::ContractError;
+use crate::migrations::MIGRATIONS;
 use crate::msg::MigrateMsg;
-use crate::state::{Config, CLAIMS, CONFIG, MAX_CLAIMS, STAKED_BALANCES, STAKED_TOTAL};
-use cosmwasm_std::{coin, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult, Uint128};
+use crate::state::{Approval, Config, UndelegationBatch, APPROVALS, BALANCE, Position, SlashEvent, CLAIMS, CLAIMS_BY_ADDR, CONFIG, EXCHANGE_RATE, HOOKS, MAX_CLAIMS, MAX_POSITIONS, MAX_UNBONDING_ENTRIES, POSITIONS, POSITION_COUNT, SLASH_EVENTS, STAKED_BALANCES, STAKED_TOTAL, UNBONDING_TOTAL, UNDELEGATION_BATCH, UNFORWARDED_REWARDS};
+use cosmwasm_std::{coin, to_json_binary, Addr, BankMsg, Binary, BlockInfo, Coins, CosmosMsg, Decimal, Deps, DepsMut, DistributionMsg, Empty, Env, MessageInfo, Reply, Response, StakingMsg, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg};
 use cw2::set_contract_version;
-use cw_controllers::ClaimsResponse;
+use cw_controllers::{Claim, ClaimsResponse};
 use cw_ownable::get_ownership;
 use cw_storage_plus::Bound;
-use symphony_interfaces::staking::{ExecuteMsg, InstantiateMsg, ListStakersResponse, QueryMsg, StakedBalanceAtHeightResponse, StakerBalanceResponse, TotalStakedAtHeightResponse};
+use cw_utils::{Duration, Expiration};
+use symphony_interfaces::orchestrator::TokenKind;
+use symphony_interfaces::staking::{ApprovalResponse, ApprovalsResponse, ConfigResponse, DelegationConfig, ExecuteMsg, InfoResponse, InstantiateMsg, ListStakersResponse, LockTier, LockTiersResponse, Permission, PositionResponse, PositionsResponse, QueryMsg, RebondOrder, ReconcileResponse, RewardsExecuteMsg, SlashEventResponse, SlashEventsResponse, SlashHookMsg, StakeChangedHookMsg, StakedBalanceAtHeightResponse, StakerBalanceResponse, TotalPowerAtHeightResponse, TotalStakedAtHeightResponse, ValidatorWeight, VotingPowerAtHeightResponse};
 use symphony_utils::duration::validate_duration;
+use symphony_utils::migrate::migrate_contract;
 
 pub(crate) const CONTRACT_NAME: &str = "crates.io:symphony-native-staking";
 pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const DELEGATION_CHANGE_REPLY_ID: u64 = 1;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -31,11 +37,21 @@ pub fn instantiate(
     let config= Config {
         staking_token: msg.denom_unit.clone(),
         unstaking_duration: msg.unbonding_period,
+        rebond_order: RebondOrder::default(),
+        lock_tiers: vec![],
+        dao: msg.dao.as_deref().map(|dao| deps.api.addr_validate(dao)).transpose()?,
+        delegation: None,
     };
 
     CONFIG.save(deps.storage, &config)?;
 
+    for hook in msg.hooks.unwrap_or_default() {
+        HOOKS.add_hook(deps.storage, deps.api.addr_validate(&hook)?)?;
+    }
+
     STAKED_TOTAL.save(deps.storage, &Uint128::zero(), env.block.height)?;
+    UNBONDING_TOTAL.save(deps.storage, &Uint128::zero())?;
+    BALANCE.save(deps.storage, &Uint128::zero())?;
     Ok(
         Response::new()
             .add_attribute("action", "instantiate")
@@ -53,9 +69,25 @@ pub fn execute(deps: DepsMut,
     match msg {
         ExecuteMsg::UpdateOwnership(action) => execute_update_owner(deps, info, env, action),
         ExecuteMsg::UpdateConfig { unbonding_period } => execute_update_config(deps, env, info, unbonding_period),
+        ExecuteMsg::UpdateSettings { rebond_order, lock_tiers, dao, delegation } => {
+            execute_update_settings(deps, info, rebond_order, lock_tiers, dao, delegation)
+        }
         ExecuteMsg::Stake {} => execute_stake(deps, env, info),
-        ExecuteMsg::Unstake { amount } => execute_unstake(deps, env, info, amount),
-        ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+        ExecuteMsg::StakeFor { recipients } => execute_stake_for(deps, env, info, recipients),
+        ExecuteMsg::StakeLocked { duration } => execute_stake_locked(deps, env, info, duration),
+        ExecuteMsg::Unstake { amount, owner } => execute_unstake(deps, env, info, amount, owner),
+        ExecuteMsg::Claim { owner } => execute_claim(deps, env, info, owner),
+        ExecuteMsg::Rebond { amount, owner } => execute_rebond(deps, env, info, amount, owner),
+        ExecuteMsg::Slash { ratio, recipient } => execute_slash(deps, env, info, ratio, recipient),
+        ExecuteMsg::Harvest {} => execute_harvest(deps, env),
+        ExecuteMsg::ProcessUndelegations {} => execute_process_undelegations(deps, env),
+        ExecuteMsg::SweepSurplus { recipient } => execute_sweep_surplus(deps, env, info, recipient),
+        ExecuteMsg::Approve { operator, expires, permissions } => {
+            execute_approve(deps, env, info, operator, expires, permissions)
+        }
+        ExecuteMsg::Revoke { operator } => execute_revoke(deps, info, operator),
+        ExecuteMsg::AddHook { addr } => execute_add_hook(deps, info, addr),
+        ExecuteMsg::RemoveHook { addr } => execute_remove_hook(deps, info, addr),
     }
 }
 
@@ -73,7 +105,7 @@ pub fn execute_update_config(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    unbonding_period: Option<cw_utils::Duration>,
+    unbonding_period: Option<Duration>,
 ) -> Result<Response, ContractError> {
     cw_ownable::assert_owner(deps.storage, &info.sender)?;
 
@@ -94,15 +126,232 @@ pub fn execute_update_config(
         ))
 }
 
-pub fn execute_stake(
+pub fn execute_update_settings(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
+    rebond_order: Option<RebondOrder>,
+    lock_tiers: Option<Vec<LockTier>>,
+    dao: Option<String>,
+    delegation: Option<DelegationConfig>,
 ) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    if let Some(lock_tiers) = &lock_tiers {
+        validate_lock_tiers(lock_tiers)?;
+    }
+    let dao = dao.map(|dao| deps.api.addr_validate(&dao)).transpose()?;
+
+    let mut msgs = vec![];
+    let delegation = match delegation {
+        None => None,
+        Some(delegation) => {
+            let delegation = validate_delegation(deps.as_ref(), delegation)?;
+            // tokens staked while idle are delegated once the mode is enabled
+            if CONFIG.load(deps.storage)?.delegation.is_none() {
+                let staked = shares_to_tokens(STAKED_TOTAL.load(deps.storage)?, exchange_rate(deps.storage)?)?;
+                let mut config = CONFIG.load(deps.storage)?;
+                config.delegation = Some(delegation.clone());
+                msgs = delegate_msgs(&config, staked)?;
+            }
+            Some(delegation)
+        }
+    };
+
+    let config = CONFIG.update(deps.storage, |mut config| -> Result<Config, StdError> {
+        if let Some(rebond_order) = rebond_order {
+            config.rebond_order = rebond_order;
+        }
+        if let Some(lock_tiers) = lock_tiers {
+            config.lock_tiers = lock_tiers;
+        }
+        if dao.is_some() {
+            config.dao = dao;
+        }
+        if delegation.is_some() {
+            config.delegation = delegation;
+        }
+        Ok(config)
+    })?;
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_attribute("action", "update_settings")
+        .add_attribute("rebond_order", format!("{:?}", config.rebond_order))
+        .add_attribute("delegation", config.delegation.is_some().to_string()))
+}
+
+fn validate_delegation(deps: Deps, mut delegation: DelegationConfig) -> Result<DelegationConfig, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let sender = info.sender;
-    deps.api.addr_validate(&sender.as_str())?;
+    if config.staking_token.denom != deps.querier.query_bonded_denom()? {
+        return Err(ContractError::InvalidDelegationDenom {});
+    }
+
+    let validators = &delegation.validators;
+    if validators.is_empty()
+        || validators.iter().any(|validator| validator.weight.is_zero())
+        || validators.iter().enumerate().any(|(i, validator)| {
+            validators[..i].iter().any(|other| other.address == validator.address)
+        })
+    {
+        return Err(ContractError::InvalidValidatorSet {});
+    }
+    for validator in validators {
+        if deps.querier.query_validator(&validator.address)?.is_none() {
+            return Err(ContractError::UnknownValidator { address: validator.address.clone() });
+        }
+    }
+
+    delegation.rewards_contract = deps.api.addr_validate(&delegation.rewards_contract)?.to_string();
+    Ok(delegation)
+}
+
+/// Splits `amount` by the validator weights, the rounding leftover going to the first one.
+fn split_by_weight(amount: Uint128, validators: &[ValidatorWeight]) -> StdResult<Vec<(String, Uint128)>> {
+    let total_weight: Uint128 = validators.iter().map(|validator| Uint128::from(validator.weight.u64())).sum();
+
+    let mut shares = validators
+        .iter()
+        .map(|validator| {
+            let share = amount.multiply_ratio(validator.weight.u64(), total_weight);
+            (validator.address.clone(), share)
+        })
+        .collect::<Vec<_>>();
 
+    let distributed: Uint128 = shares.iter().map(|(_, share)| *share).sum();
+    if let Some((_, share)) = shares.first_mut() {
+        *share = share.checked_add(amount.checked_sub(distributed)?)?;
+    }
+
+    Ok(shares)
+}
+
+/// Delegates `amount` across the validators, nothing when delegation is disabled.
+fn delegate_msgs(config: &Config, amount: Uint128) -> StdResult<Vec<SubMsg>> {
+    let Some(delegation) = &config.delegation else {
+        return Ok(vec![]);
+    };
+
+    Ok(split_by_weight(amount, &delegation.validators)?
+        .into_iter()
+        .filter(|(_, share)| !share.is_zero())
+        .map(|(validator, share)| delegation_change_msg(StakingMsg::Delegate {
+            validator,
+            amount: coin(share.u128(), config.staking_token.denom.as_str()),
+        }))
+        .collect())
+}
+
+/// Undelegates `amount` following the validator weights, taking what a validator lacks from
+/// the other delegations, including validators removed from the set.
+fn undelegate_msgs(
+    deps: Deps,
+    env: &Env,
+    delegation: &DelegationConfig,
+    denom: &str,
+    amount: Uint128,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let mut delegated = deps.querier
+        .query_all_delegations(&env.contract.address)?
+        .into_iter()
+        .map(|delegation| (delegation.validator, delegation.amount.amount))
+        .collect::<Vec<_>>();
+    let all_validators = delegated.iter().map(|(validator, _)| validator.clone()).collect::<Vec<_>>();
+
+    let mut undelegations: Vec<(String, Uint128)> = vec![];
+    let mut take = |validator: &str, wanted: Uint128| {
+        if let Some((_, available)) = delegated.iter_mut().find(|(address, _)| address == validator) {
+            let taken = wanted.min(*available);
+            *available -= taken;
+            match undelegations.iter_mut().find(|(address, _)| address == validator) {
+                Some((_, total)) => *total += taken,
+                None => undelegations.push((validator.to_string(), taken)),
+            }
+            return taken;
+        }
+        Uint128::zero()
+    };
+
+    let mut remaining = amount;
+    for (validator, share) in split_by_weight(amount, &delegation.validators)? {
+        remaining -= take(&validator, share);
+    }
+    for validator in all_validators {
+        if remaining.is_zero() {
+            break;
+        }
+        remaining -= take(&validator, remaining);
+    }
+    if !remaining.is_zero() {
+        return Err(ContractError::InvalidUnstakeAmount {});
+    }
+
+    Ok(undelegations
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(validator, amount)| delegation_change_msg(StakingMsg::Undelegate {
+            validator,
+            amount: coin(amount.u128(), denom),
+        }))
+        .collect())
+}
+
+/// The chain withdraws the rewards of a delegation whenever it changes, so the reply keeps
+/// track of them for the next harvest.
+fn delegation_change_msg(msg: StakingMsg) -> SubMsg {
+    SubMsg::reply_on_success(msg, DELEGATION_CHANGE_REPLY_ID)
+}
+
+/// Claims wait until the chain unbonding of their batch is over, or the contract period when
+/// it is a longer time. This assumes the batch is undelegated as soon as it is due. A batch
+/// processed late comes back after its claims are released, and claiming them fails until
+/// it does, unless other tokens held by the contract cover them.
+fn delegated_claim_duration(unstaking_duration: Option<Duration>, unbonding_time: u64, undelegation_delay: u64) -> Duration {
+    let unbonded_after = undelegation_delay + unbonding_time;
+    match unstaking_duration {
+        Some(Duration::Time(time)) if time > unbonded_after => Duration::Time(time),
+        _ => Duration::Time(unbonded_after),
+    }
+}
+
+/// Seconds between two undelegations, so that no more than `MAX_UNBONDING_ENTRIES` per
+/// validator are unbonding at once.
+fn undelegation_interval(unbonding_time: u64) -> u64 {
+    unbonding_time.div_ceil(MAX_UNBONDING_ENTRIES - 1)
+}
+
+/// Undelegates the pending batch when it is due, nothing otherwise.
+fn undelegate_batch(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    delegation: &DelegationConfig,
+) -> Result<(Vec<SubMsg>, Uint128), ContractError> {
+    let batch = UNDELEGATION_BATCH.may_load(deps.storage)?.unwrap_or_default();
+    if batch.amount.is_zero() || env.block.time < batch.next_undelegation {
+        return Ok((vec![], Uint128::zero()));
+    }
+
+    let msgs = undelegate_msgs(deps.as_ref(), env, delegation, &config.staking_token.denom, batch.amount)?;
+    UNDELEGATION_BATCH.save(deps.storage, &UndelegationBatch {
+        amount: Uint128::zero(),
+        next_undelegation: env.block.time.plus_seconds(undelegation_interval(delegation.unbonding_time)),
+    })?;
+    Ok((msgs, batch.amount))
+}
+
+
+fn validate_lock_tiers(lock_tiers: &[LockTier]) -> Result<(), ContractError> {
+    for lock_tier in lock_tiers {
+        validate_duration(Some(lock_tier.duration))?;
+        if lock_tier.multiplier < Decimal::one() {
+            return Err(ContractError::InvalidLockMultiplier {});
+        }
+    }
+    Ok(())
+}
+
+/// Amount of staking tokens sent along with a stake message.
+fn stake_amount(config: &Config, info: &MessageInfo) -> Result<Uint128, ContractError> {
     if info.funds.is_empty() {
         return Err(ContractError::NoStakeAmount {});
     }
@@ -118,38 +367,227 @@ pub fn execute_stake(
         return Err(ContractError::NoStakeAmount {});
     }
 
-    STAKED_BALANCES.update(
-        deps.storage,
-        &sender,
-        env.block.height,
-        |bal| -> StdResult<Uint128> { Ok(bal.unwrap_or_default().checked_add(amount_to_stake)?) },
+    Ok(amount_to_stake)
+}
+
+pub fn execute_stake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let sender = info.sender.clone();
+    deps.api.addr_validate(sender.as_str())?;
+
+    let amount_to_stake = stake_amount(&config, &info)?;
+    let shares = tokens_to_shares_floor(amount_to_stake, exchange_rate(deps.storage)?)?;
+    add_to_ledger(deps.storage, amount_to_stake)?;
+
+    let hook_msgs = add_stake(deps.storage, env.block.height, &sender, shares, &config.staking_token.denom)?;
+
+    Ok(Response::new()
+        .add_submessages(delegate_msgs(&config, amount_to_stake)?)
+        .add_submessages(hook_msgs)
+        .add_attribute("action", "stake")
+        .add_attribute("from", sender)
+        .add_attribute("denom", config.staking_token.denom)
+        .add_attribute("amount", amount_to_stake))
+}
+
+pub fn execute_stake_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount_to_stake = stake_amount(&config, &info)?;
+
+    let recipients = recipients.into_iter()
+        .map(|(recipient, amount)| {
+            if amount.is_zero() {
+                return Err(ContractError::NoStakeAmount {});
+            }
+            Ok((deps.api.addr_validate(&recipient)?, amount))
+        })
+        .collect::<Result<Vec<(Addr, Uint128)>, ContractError>>()?;
+
+    let expected = recipients.iter()
+        .try_fold(Uint128::zero(), |total, (_, amount)| total.checked_add(*amount))
+        .map_err(StdError::from)?;
+    if expected != amount_to_stake {
+        return Err(ContractError::StakeForFundsMismatch { expected, received: amount_to_stake });
+    }
+
+    let rate = exchange_rate(deps.storage)?;
+    add_to_ledger(deps.storage, amount_to_stake)?;
+
+    let mut response = Response::new()
+        .add_submessages(delegate_msgs(&config, amount_to_stake)?)
+        .add_attribute("action", "stake_for")
+        .add_attribute("from", info.sender)
+        .add_attribute("denom", config.staking_token.denom.as_str())
+        .add_attribute("amount", amount_to_stake);
+
+    for (recipient, amount) in recipients {
+        let shares = tokens_to_shares_floor(amount, rate)?;
+        let hook_msgs = add_stake(deps.storage, env.block.height, &recipient, shares, &config.staking_token.denom)?;
+        response = response
+            .add_submessages(hook_msgs)
+            .add_attribute("recipient", recipient)
+            .add_attribute("recipient_amount", amount);
+    }
+
+    Ok(response)
+}
+
+pub fn execute_stake_locked(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    duration: Duration,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount_to_stake = stake_amount(&config, &info)?;
+
+    let lock_tier = config.lock_tiers
+        .iter()
+        .find(|lock_tier| lock_tier.duration == duration)
+        .ok_or(ContractError::InvalidLockTier {})?;
+
+    let (_, active_positions) = prune_positions(deps.storage, &info.sender, &env.block)?;
+    if active_positions >= MAX_POSITIONS as usize {
+        return Err(ContractError::TooManyPositions {});
+    }
+
+    let id = POSITION_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    POSITION_COUNT.save(deps.storage, &id)?;
+
+    let shares = tokens_to_shares_floor(amount_to_stake, exchange_rate(deps.storage)?)?;
+    add_to_ledger(deps.storage, amount_to_stake)?;
+    let position = Position {
+        shares,
+        lock_duration: duration,
+        unlock_at: duration.after(&env.block),
+        multiplier: lock_tier.multiplier,
+    };
+    POSITIONS.save(deps.storage, (&info.sender, id), &position)?;
+
+    let hook_msgs = add_stake(deps.storage, env.block.height, &info.sender, shares, &config.staking_token.denom)?;
+
+    Ok(Response::new()
+        .add_submessages(delegate_msgs(&config, amount_to_stake)?)
+        .add_submessages(hook_msgs)
+        .add_attribute("action", "stake_locked")
+        .add_attribute("from", info.sender)
+        .add_attribute("denom", config.staking_token.denom)
+        .add_attribute("amount", amount_to_stake)
+        .add_attribute("position_id", id.to_string())
+        .add_attribute("unlock_at", position.unlock_at.to_string()))
+}
+
+/// Removes the expired positions of `addr`, whose stake is no longer locked. Returns the
+/// shares still locked and the number of positions left.
+fn prune_positions(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    block: &BlockInfo,
+) -> StdResult<(Uint128, usize)> {
+    let positions = POSITIONS
+        .prefix(addr)
+        .range(storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<(u64, Position)>>>()?;
+
+    let mut locked = Uint128::zero();
+    let mut active_positions = 0;
+    for (id, position) in positions {
+        if position.unlock_at.is_expired(block) {
+            POSITIONS.remove(storage, (addr, id));
+        } else {
+            locked = locked.checked_add(position.shares)?;
+            active_positions += 1;
+        }
+    }
+
+    Ok((locked, active_positions))
+}
+
+fn exchange_rate(storage: &dyn Storage) -> StdResult<Decimal> {
+    Ok(EXCHANGE_RATE.may_load(storage)?.unwrap_or(Decimal::one()))
+}
+
+fn exchange_rate_at_height(storage: &dyn Storage, height: u64) -> StdResult<Decimal> {
+    Ok(EXCHANGE_RATE.may_load_at_height(storage, height)?.unwrap_or(Decimal::one()))
+}
+
+fn shares_to_tokens(shares: Uint128, rate: Decimal) -> StdResult<Uint128> {
+    shares.checked_mul_floor(rate).map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+fn tokens_to_shares_floor(tokens: Uint128, rate: Decimal) -> StdResult<Uint128> {
+    tokens.checked_div_floor(rate).map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+/// Shares needed to take `tokens` out of a balance, rounded against the user.
+fn tokens_to_shares_ceil(tokens: Uint128, rate: Decimal) -> StdResult<Uint128> {
+    tokens.checked_div_ceil(rate).map_err(|e| StdError::generic_err(e.to_string()))
+}
+
+fn add_to_ledger(storage: &mut dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    BALANCE.update(storage, |balance| -> StdResult<_> { Ok(balance.checked_add(amount)?) })
+}
+
+fn subtract_from_ledger(storage: &mut dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    BALANCE.update(storage, |balance| -> StdResult<_> { Ok(balance.checked_sub(amount)?) })
+}
+
+/// Adds `shares` to the stake of `addr` and the total at `height`, returning the hook
+/// messages notifying the change.
+fn add_stake(
+    storage: &mut dyn Storage,
+    height: u64,
+    addr: &Addr,
+    shares: Uint128,
+    denom: &str,
+) -> StdResult<Vec<SubMsg>> {
+    let new_shares = STAKED_BALANCES.update(
+        storage,
+        addr,
+        height,
+        |bal| -> StdResult<Uint128> { Ok(bal.unwrap_or_default().checked_add(shares)?) },
     )?;
     STAKED_TOTAL.update(
-        deps.storage,
-        env.block.height,
+        storage,
+        height,
         |total| -> StdResult<Uint128> {
             // Initialized during instantiate - OK to unwrap.
-            Ok(total.unwrap().checked_add(amount_to_stake)?)
+            Ok(total.unwrap().checked_add(shares)?)
         },
     )?;
 
-    Ok(Response::new()
-        .add_attribute("action", "stake")
-        .add_attribute("from", sender)
-        .add_attribute("denom", config.staking_token.denom)
-        .add_attribute("amount", amount_to_stake))
+    let rate = exchange_rate(storage)?;
+    stake_changed_hook_msgs(
+        storage,
+        addr,
+        shares_to_tokens(new_shares.checked_sub(shares)?, rate)?,
+        shares_to_tokens(new_shares, rate)?,
+        denom,
+    )
 }
 
 pub fn execute_unstake(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     amount: Uint128,
+    owner: Option<String>,
 ) -> Result<Response, ContractError> {
+    let staker = approved_staker(deps.as_ref(), &env, &info.sender, owner, Permission::Unstake)?;
     let config = CONFIG.load(deps.storage)?;
-    let staked_total = STAKED_TOTAL.load(deps.storage)?;
-    let user_balance = STAKED_BALANCES.load(deps.storage, &info.sender);
-    if user_balance.is_err() {
+    let rate = exchange_rate(deps.storage)?;
+    let staked_total = shares_to_tokens(STAKED_TOTAL.load(deps.storage)?, rate)?;
+    let user_shares = STAKED_BALANCES.load(deps.storage, &staker);
+    if user_shares.is_err() {
         return Err(ContractError::NoUnstakeAmount {});
     }
 
@@ -165,68 +603,99 @@ pub fn execute_unstake(
         return Err(ContractError::InvalidUnstakeAmount {});
     }
 
-    if amount > user_balance? {
+    let user_shares = user_shares?;
+    if amount > shares_to_tokens(user_shares, rate)? {
         return Err(ContractError::InvalidUnstakeAmount {});
     }
 
-    STAKED_BALANCES.update(
+    let shares = tokens_to_shares_ceil(amount, rate)?;
+    let (locked, _) = prune_positions(deps.storage, &staker, &env.block)?;
+    if shares > user_shares.saturating_sub(locked) {
+        return Err(ContractError::StakeLocked {});
+    }
+
+    let new_shares = STAKED_BALANCES.update(
         deps.storage,
-        &info.sender,
+        &staker,
         env.block.height,
-        |bal| -> StdResult<Uint128> { Ok(bal.unwrap_or_default().checked_sub(amount)?) },
+        |bal| -> StdResult<Uint128> { Ok(bal.unwrap_or_default().checked_sub(shares)?) },
     )?;
     STAKED_TOTAL.update(
         deps.storage,
         env.block.height,
         |total| -> StdResult<Uint128> {
             // Initialized during instantiate - OK to unwrap.
-            Ok(total.unwrap().checked_sub(amount)?)
+            Ok(total.unwrap().checked_sub(shares)?)
         },
     )?;
 
-    match config.unstaking_duration {
+    let hook_msgs = stake_changed_hook_msgs(
+        deps.storage,
+        &staker,
+        shares_to_tokens(user_shares, rate)?,
+        shares_to_tokens(new_shares, rate)?,
+        &config.staking_token.denom,
+    )?;
+
+    // the chain caps the unbonding entries, so unstaked tokens are undelegated in batches
+    let mut undelegate_msgs = vec![];
+    let claim_duration = match &config.delegation {
+        None => config.unstaking_duration,
+        Some(delegation) => {
+            let mut batch = UNDELEGATION_BATCH.may_load(deps.storage)?.unwrap_or_default();
+            batch.amount = batch.amount.checked_add(amount).map_err(StdError::from)?;
+            UNDELEGATION_BATCH.save(deps.storage, &batch)?;
+            let (msgs, _) = undelegate_batch(deps.branch(), &env, &config, delegation)?;
+            undelegate_msgs = msgs;
+
+            let undelegation_delay = match UNDELEGATION_BATCH.load(deps.storage)? {
+                batch if batch.amount.is_zero() => 0,
+                batch => batch.next_undelegation.seconds().saturating_sub(env.block.time.seconds()),
+            };
+            Some(delegated_claim_duration(config.unstaking_duration, delegation.unbonding_time, undelegation_delay))
+        }
+    };
+
+    match claim_duration {
         None => {
             // send the tokens back to the sender
-            let contract_balance = deps.querier.query_balance(
-                env.contract.address,
-                config.staking_token.denom.clone()
-            );
-
-            match contract_balance {
-                Ok(balance) => {
-                    if balance.amount >= amount {
-                        let msg: BankMsg = BankMsg::Send {
-                            to_address: info.sender.to_string(),
-                            amount: vec![
-                                coin(amount.u128(), config.staking_token.denom.as_str())
-                            ],
-                        };
-                        Ok(
-                            Response::new()
-                                .add_message(msg)
-                                .add_attribute("action", "unstake")
-                                .add_attribute("from", info.sender)
-                                .add_attribute("denom", config.staking_token.denom)
-                                .add_attribute("amount", amount)
-                        )
-                    } else {
-                        Err(ContractError::InvalidUnstakeAmount {})
-                    }
-                },
-                Err(_) => Err(ContractError::NoUnstakeAmount {})
+            if BALANCE.load(deps.storage)? < amount {
+                return Err(ContractError::InvalidUnstakeAmount {});
             }
+            subtract_from_ledger(deps.storage, amount)?;
+
+            let msg: BankMsg = BankMsg::Send {
+                to_address: staker.to_string(),
+                amount: vec![
+                    coin(amount.u128(), config.staking_token.denom.as_str())
+                ],
+            };
+            Ok(
+                Response::new()
+                    .add_message(msg)
+                    .add_submessages(hook_msgs)
+                    .add_attribute("action", "unstake")
+                    .add_attribute("from", staker)
+                    .add_attribute("denom", config.staking_token.denom)
+                    .add_attribute("amount", amount)
+            )
         }
         Some(duration) => {
-            let pending_claims = CLAIMS.query_claims(deps.as_ref(), &info.sender)?.claims;
+            let pending_claims = CLAIMS.query_claims(deps.as_ref(), &staker)?.claims;
             if pending_claims.len() >= MAX_CLAIMS as usize {
                 return Err(ContractError::TooManyClaims {});
             }
 
-            CLAIMS.create_claim(deps.storage, &info.sender, amount, duration.after(&env.block))?;
+            CLAIMS.create_claim(deps.storage, &staker, shares, duration.after(&env.block))?;
+            UNBONDING_TOTAL.update(deps.storage, |total| -> StdResult<_> {
+                Ok(total.checked_add(shares)?)
+            })?;
 
             Ok(Response::new()
+                .add_submessages(undelegate_msgs)
+                .add_submessages(hook_msgs)
                 .add_attribute("action", "unstake")
-                .add_attribute("from", info.sender)
+                .add_attribute("from", staker)
                 .add_attribute("denom", config.staking_token.denom)
                 .add_attribute("amount", amount)
                 .add_attribute("claim_duration",format!("{duration}")))
@@ -238,34 +707,430 @@ pub fn execute_claim(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    owner: Option<String>,
 ) -> Result<Response, ContractError> {
-    let mature_claims = CLAIMS.claim_tokens(deps.storage, &info.sender, &env.block, None)?;
-    if mature_claims.is_zero() {
+    let staker = approved_staker(deps.as_ref(), &env, &info.sender, owner, Permission::Claim)?;
+    let mature_shares = CLAIMS.claim_tokens(deps.storage, &staker, &env.block, None)?;
+    if mature_shares.is_zero() {
         return Err(ContractError::NothingToClaim {})
     }
+    UNBONDING_TOTAL.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total.checked_sub(mature_shares)?)
+    })?;
+
+    let mature_claims = shares_to_tokens(mature_shares, exchange_rate(deps.storage)?)?;
+    subtract_from_ledger(deps.storage, mature_claims)?;
     let config = CONFIG.load(deps.storage)?;
     let msg: BankMsg = BankMsg::Send {
-        to_address: info.sender.to_string(),
+        to_address: staker.to_string(),
         amount: vec![coin(mature_claims.u128(), config.staking_token.denom.as_str())],
     };
     Ok(Response::new()
         .add_message(msg)
         .add_attribute("action", "claim")
-        .add_attribute("from", info.sender)
+        .add_attribute("from", staker)
         .add_attribute("denom", config.staking_token.denom)
         .add_attribute("amount", mature_claims))
 }
 
-//TODO: Implement migration logic
+pub fn execute_rebond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    owner: Option<String>,
+) -> Result<Response, ContractError> {
+    let staker = approved_staker(deps.as_ref(), &env, &info.sender, owner, Permission::Rebond)?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidRebondAmount {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    // unbonding tokens only come back once the chain unbonding is over
+    if config.delegation.is_some() {
+        return Err(ContractError::RebondWhileDelegating {});
+    }
+    let mut claims = CLAIMS_BY_ADDR.may_load(deps.storage, &staker)?.unwrap_or_default();
+
+    // claims are stored in creation order, mature ones can be claimed instead
+    let mut pending = (0..claims.len())
+        .filter(|i| !claims[*i].release_at.is_expired(&env.block))
+        .collect::<Vec<usize>>();
+    if config.rebond_order == RebondOrder::NewestFirst {
+        pending.reverse();
+    }
+
+    let shares = tokens_to_shares_ceil(amount, exchange_rate(deps.storage)?)?;
+    let mut remaining = shares;
+    for i in pending {
+        if remaining.is_zero() {
+            break;
+        }
+        let rebonded = claims[i].amount.min(remaining);
+        claims[i].amount -= rebonded;
+        remaining -= rebonded;
+    }
+
+    if !remaining.is_zero() {
+        return Err(ContractError::InvalidRebondAmount {});
+    }
+
+    claims.retain(|claim| !claim.amount.is_zero());
+    CLAIMS_BY_ADDR.save(deps.storage, &staker, &claims)?;
+    UNBONDING_TOTAL.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total.checked_sub(shares)?)
+    })?;
+
+    let hook_msgs = add_stake(deps.storage, env.block.height, &staker, shares, &config.staking_token.denom)?;
+
+    Ok(Response::new()
+        .add_submessages(hook_msgs)
+        .add_attribute("action", "rebond")
+        .add_attribute("from", staker)
+        .add_attribute("denom", config.staking_token.denom)
+        .add_attribute("amount", amount))
+}
+
+/// Lowers the exchange rate instead of rewriting every balance, so slashing costs the
+/// same for any number of stakers. Hooks get the new exchange rate in a single slash hook
+/// rather than a stake changed hook per staker.
+pub fn execute_slash(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ratio: Decimal,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    if ratio.is_zero() || ratio >= Decimal::one() {
+        return Err(ContractError::InvalidSlashRatio {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    // slashed tokens are taken from the bank balance, which holds none of the delegated stake
+    if config.delegation.is_some() {
+        return Err(ContractError::SlashWhileDelegating {});
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let total_shares = STAKED_TOTAL.load(deps.storage)?
+        .checked_add(UNBONDING_TOTAL.may_load(deps.storage)?.unwrap_or_default())
+        .map_err(StdError::from)?;
+
+    let old_rate = exchange_rate(deps.storage)?;
+    let new_rate = old_rate * (Decimal::one() - ratio);
+
+    let amount = shares_to_tokens(total_shares, old_rate)?
+        .checked_sub(shares_to_tokens(total_shares, new_rate)?)
+        .map_err(StdError::from)?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToSlash {});
+    }
+
+    EXCHANGE_RATE.save(deps.storage, &new_rate, env.block.height)?;
+    subtract_from_ledger(deps.storage, amount)?;
+    SLASH_EVENTS.update(deps.storage, env.block.height, |events| -> StdResult<_> {
+        let mut events = events.unwrap_or_default();
+        events.push(SlashEvent {
+            ratio,
+            amount,
+            recipient: recipient.clone(),
+            exchange_rate: new_rate,
+        });
+        Ok(events)
+    })?;
+
+    // every balance shrinks without a stake changed hook, so hooks get the new exchange rate
+    let hook_msg = SlashHookMsg {
+        denom: config.staking_token.denom.clone(),
+        exchange_rate: new_rate,
+    };
+    let hook_msgs = HOOKS.prepare_hooks(deps.storage, |hook| {
+        hook_msg.clone().into_cosmos_msg(hook).map(SubMsg::new)
+    })?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.u128(), config.staking_token.denom.as_str())],
+        })
+        .add_submessages(hook_msgs)
+        .add_attribute("action", "slash")
+        .add_attribute("ratio", ratio.to_string())
+        .add_attribute("recipient", recipient)
+        .add_attribute("denom", config.staking_token.denom)
+        .add_attribute("amount", amount)
+        .add_attribute("exchange_rate", new_rate.to_string()))
+}
+
+/// Anyone can harvest, the rewards always go to the configured rewards contract, along with
+/// those the chain withdrew when delegations changed.
+pub fn execute_harvest(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let Some(delegation) = config.delegation else {
+        return Err(ContractError::DelegationDisabled {});
+    };
+
+    let mut withdraw_msgs: Vec<CosmosMsg> = vec![];
+    let mut rewards = Coins::try_from(UNFORWARDED_REWARDS.may_load(deps.storage)?.unwrap_or_default()).map_err(StdError::from)?;
+    for validator in deps.querier.query_all_delegations(&env.contract.address)? {
+        let Some(full_delegation) = deps.querier.query_delegation(&env.contract.address, &validator.validator)? else {
+            continue;
+        };
+        if full_delegation.accumulated_rewards.iter().all(|reward| reward.amount.is_zero()) {
+            continue;
+        }
+        for reward in full_delegation.accumulated_rewards {
+            rewards.add(reward)?;
+        }
+        withdraw_msgs.push(DistributionMsg::WithdrawDelegatorReward { validator: validator.validator }.into());
+    }
+
+    if rewards.is_empty() {
+        return Err(ContractError::NothingToHarvest {});
+    }
+    UNFORWARDED_REWARDS.remove(deps.storage);
+
+    // withdrawals run first, so the rewards are in the contract balance when forwarded
+    let forward_msg = WasmMsg::Execute {
+        contract_addr: delegation.rewards_contract.clone(),
+        msg: to_json_binary(&RewardsExecuteMsg::DistributeRewards {})?,
+        funds: rewards.to_vec(),
+    };
+
+    Ok(Response::new()
+        .add_messages(withdraw_msgs)
+        .add_message(forward_msg)
+        .add_attribute("action", "harvest")
+        .add_attribute("rewards_contract", delegation.rewards_contract)
+        .add_attribute("rewards", rewards.to_string()))
+}
+
+/// Anyone can trigger the undelegation of a batch once it is due.
+pub fn execute_process_undelegations(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let Some(delegation) = &config.delegation else {
+        return Err(ContractError::DelegationDisabled {});
+    };
+
+    let (msgs, amount) = undelegate_batch(deps.branch(), &env, &config, delegation)?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToUndelegate {});
+    }
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_attribute("action", "process_undelegations")
+        .add_attribute("denom", config.staking_token.denom)
+        .add_attribute("amount", amount))
+}
+
+pub fn execute_sweep_surplus(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let reconciliation = reconcile(deps.as_ref(), &env)?;
+    let mut surplus = Coins::default();
+    for other in reconciliation.other_balances {
+        surplus.add(other)?;
+    }
+    // staking tokens being delegated or undelegated are never in the bank balance to sweep,
+    // and neither are the rewards waiting for a harvest
+    let unforwarded = reconciliation.unforwarded_rewards
+        .iter()
+        .find(|reward| reward.denom == reconciliation.denom)
+        .map(|reward| reward.amount)
+        .unwrap_or_default();
+    let held_surplus = reconciliation.surplus.min(reconciliation.balance.saturating_sub(unforwarded));
+    if !held_surplus.is_zero() {
+        surplus.add(coin(held_surplus.u128(), reconciliation.denom.as_str()))?;
+    }
+
+    if surplus.is_empty() {
+        return Err(ContractError::NothingToSweep {});
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: surplus.to_vec(),
+        })
+        .add_attribute("action", "sweep_surplus")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", surplus.to_string()))
+}
+
+pub fn execute_approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+    permissions: Vec<Permission>,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    if permissions.is_empty() {
+        return Err(ContractError::NoPermissions {});
+    }
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::ApprovalExpired {});
+    }
+
+    APPROVALS.save(deps.storage, (&info.sender, &operator), &Approval {
+        expires,
+        permissions: permissions.clone(),
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "approve")
+        .add_attribute("owner", info.sender)
+        .add_attribute("operator", operator)
+        .add_attribute("expires", expires.to_string())
+        .add_attribute("permissions", permissions.iter().map(Permission::to_string).collect::<Vec<_>>().join(",")))
+}
+
+pub fn execute_revoke(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    APPROVALS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke")
+        .add_attribute("owner", info.sender)
+        .add_attribute("operator", operator))
+}
+
+/// Address an action applies to: the sender, or `owner` if it approved the sender for
+/// `permission`.
+fn approved_staker(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    owner: Option<String>,
+    permission: Permission,
+) -> Result<Addr, ContractError> {
+    let owner = match owner {
+        None => return Ok(sender.clone()),
+        Some(owner) => deps.api.addr_validate(&owner)?,
+    };
+    if owner == sender {
+        return Ok(owner);
+    }
+
+    match APPROVALS.may_load(deps.storage, (&owner, sender))? {
+        Some(approval) if !approval.expires.is_expired(&env.block) && approval.permissions.contains(&permission) => Ok(owner),
+        _ => Err(ContractError::NotApproved { permission }),
+    }
+}
+
+pub fn execute_add_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let hook = deps.api.addr_validate(&addr)?;
+    HOOKS.add_hook(deps.storage, hook)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_hook")
+        .add_attribute("hook", addr))
+}
+
+pub fn execute_remove_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let hook = deps.api.addr_validate(&addr)?;
+    HOOKS.remove_hook(deps.storage, hook)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_hook")
+        .add_attribute("hook", addr))
+}
+
+fn stake_changed_hook_msgs(
+    storage: &dyn Storage,
+    addr: &Addr,
+    old_balance: Uint128,
+    new_balance: Uint128,
+    denom: &str,
+) -> StdResult<Vec<SubMsg>> {
+    let msg = StakeChangedHookMsg {
+        addr: addr.to_string(),
+        old_balance,
+        new_balance,
+        denom: denom.to_string(),
+    };
+
+    HOOKS.prepare_hooks(storage, |hook| {
+        msg.clone().into_cosmos_msg(hook).map(SubMsg::new)
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response<Empty>, ContractError> {
-    Ok(Response::new())
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response<Empty>, ContractError> {
+    let from_version = migrate_contract(deps.storage, &env, CONTRACT_NAME, CONTRACT_VERSION, MIGRATIONS)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        DELEGATION_CHANGE_REPLY_ID => handle_delegation_change_reply(deps, msg),
+        id => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+/// Records the rewards the chain reports as withdrawn in `withdraw_rewards` events.
+fn handle_delegation_change_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let response = msg.result.into_result().map_err(StdError::generic_err)?;
+
+    let mut withdrawn = Coins::default();
+    for event in response.events.iter().filter(|event| event.ty == "withdraw_rewards") {
+        for attribute in event.attributes.iter().filter(|attribute| attribute.key == "amount") {
+            for reward in attribute.value.parse::<Coins>()? {
+                withdrawn.add(reward)?;
+            }
+        }
+    }
+    if withdrawn.is_empty() {
+        return Ok(Response::new());
+    }
+
+    let mut unforwarded = Coins::try_from(UNFORWARDED_REWARDS.may_load(deps.storage)?.unwrap_or_default()).map_err(StdError::from)?;
+    for reward in withdrawn.clone() {
+        unforwarded.add(reward)?;
+    }
+    UNFORWARDED_REWARDS.save(deps.storage, &unforwarded.into_vec())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "record_withdrawn_rewards")
+        .add_attribute("rewards", withdrawn.to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Config {} => query_config(deps),
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
         QueryMsg::Ownership {} => to_json_binary(&get_ownership(deps.storage)?),
         QueryMsg::StakedBalanceAtHeight { address, height } => to_json_binary(&query_staked_balance(deps, env, address, height)?),
         QueryMsg::TotalStakedAtHeight { height } => to_json_binary(&query_total_staked_at_height(deps, env, height)?),
@@ -273,18 +1138,99 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::ListStakers { start_after, limit } => {
             to_json_binary(&query_all_stakers(deps, start_after, limit)?)
         }
+        QueryMsg::Hooks {} => to_json_binary(&HOOKS.query_hooks(deps)?),
+        QueryMsg::LockTiers {} => to_json_binary(&query_lock_tiers(deps)?),
+        QueryMsg::Positions { address } => to_json_binary(&query_positions(deps, env, address)?),
+        QueryMsg::VotingPowerAtHeight { address, height } => {
+            to_json_binary(&query_voting_power_at_height(deps, env, address, height)?)
+        }
+        QueryMsg::TotalPowerAtHeight { height } => to_json_binary(&query_total_power_at_height(deps, env, height)?),
+        QueryMsg::Dao {} => to_json_binary(&query_dao(deps)?),
+        QueryMsg::Info {} => to_json_binary(&query_info(deps)?),
+        QueryMsg::SlashEvents { start_height, end_height } => {
+            to_json_binary(&query_slash_events(deps, start_height, end_height)?)
+        }
+        QueryMsg::Reconcile {} => to_json_binary(&reconcile(deps, &env)?),
+        QueryMsg::Approvals { owner, start_after, limit } => {
+            to_json_binary(&query_approvals(deps, env, owner, start_after, limit)?)
+        }
     }
 }
 
-pub fn query_config(deps: Deps) -> StdResult<Binary> {
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        staking_token: config.staking_token,
+        unstaking_duration: config.unstaking_duration,
+        rebond_order: config.rebond_order,
+        lock_tiers: config.lock_tiers,
+        dao: config.dao,
+        delegation: config.delegation,
+        token_kind: TokenKind::Native,
+    })
+}
+
+pub fn query_lock_tiers(deps: Deps) -> StdResult<LockTiersResponse> {
     let config = CONFIG.load(deps.storage)?;
-    to_json_binary(&config)
+    Ok(LockTiersResponse { lock_tiers: config.lock_tiers })
+}
+
+pub fn query_positions(deps: Deps, env: Env, address: String) -> StdResult<PositionsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let rate = exchange_rate(deps.storage)?;
+
+    let positions = POSITIONS
+        .prefix(&address)
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .filter(|item| !matches!(item, Ok((_, position)) if position.unlock_at.is_expired(&env.block)))
+        .map(|item| {
+            let (id, position) = item?;
+            Ok(PositionResponse {
+                id,
+                amount: shares_to_tokens(position.shares, rate)?,
+                lock_duration: position.lock_duration,
+                unlock_at: position.unlock_at,
+                multiplier: position.multiplier,
+            })
+        })
+        .collect::<StdResult<Vec<PositionResponse>>>()?;
+
+    Ok(PositionsResponse { positions })
+}
+
+pub fn query_voting_power_at_height(
+    deps: Deps,
+    env: Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerAtHeightResponse> {
+    let staked = query_staked_balance(deps, env, address, height)?;
+    Ok(VotingPowerAtHeightResponse { power: staked.balance, height: staked.height })
+}
+
+pub fn query_total_power_at_height(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalPowerAtHeightResponse> {
+    let total = query_total_staked_at_height(deps, env, height)?;
+    Ok(TotalPowerAtHeightResponse { power: total.total, height: total.height })
+}
+
+pub fn query_dao(deps: Deps) -> StdResult<Addr> {
+    CONFIG.load(deps.storage)?.dao.ok_or_else(|| StdError::not_found("dao"))
+}
+
+pub fn query_info(deps: Deps) -> StdResult<InfoResponse> {
+    let info = cw2::get_contract_version(deps.storage)?;
+    Ok(InfoResponse { info })
 }
 
 pub fn query_staked_balance(deps: Deps, env: Env, address: String, height: Option<u64>) -> StdResult<StakedBalanceAtHeightResponse> {
     let query_address = deps.api.addr_validate(&address)?;
     let query_height = height.unwrap_or(env.block.height);
-    let balance = STAKED_BALANCES.may_load_at_height(deps.storage, &query_address, query_height)?.unwrap_or_default();
+    let shares = STAKED_BALANCES.may_load_at_height(deps.storage, &query_address, query_height)?.unwrap_or_default();
+    let balance = shares_to_tokens(shares, exchange_rate_at_height(deps.storage, query_height)?)?;
     Ok(StakedBalanceAtHeightResponse { balance, height: query_height })
 }
 
@@ -294,9 +1240,10 @@ pub fn query_total_staked_at_height(
     height: Option<u64>,
 ) -> StdResult<TotalStakedAtHeightResponse> {
     let height = height.unwrap_or(_env.block.height);
-    let total = STAKED_TOTAL
+    let shares = STAKED_TOTAL
         .may_load_at_height(deps.storage, height)?
         .unwrap_or_default();
+    let total = shares_to_tokens(shares, exchange_rate_at_height(deps.storage, height)?)?;
     Ok(TotalStakedAtHeightResponse { total, height })
 }
 
@@ -304,7 +1251,44 @@ pub fn query_claims(
     deps: Deps,
     address: String,
 ) -> StdResult<ClaimsResponse> {
-    CLAIMS.query_claims(deps, &deps.api.addr_validate(&address)?)
+    let rate = exchange_rate(deps.storage)?;
+    let claims = CLAIMS.query_claims(deps, &deps.api.addr_validate(&address)?)?.claims
+        .into_iter()
+        .map(|claim| Ok(Claim {
+            amount: shares_to_tokens(claim.amount, rate)?,
+            release_at: claim.release_at,
+        }))
+        .collect::<StdResult<Vec<Claim>>>()?;
+
+    Ok(ClaimsResponse { claims })
+}
+
+pub fn query_slash_events(
+    deps: Deps,
+    start_height: Option<u64>,
+    end_height: Option<u64>,
+) -> StdResult<SlashEventsResponse> {
+    let slash_events = SLASH_EVENTS
+        .range(
+            deps.storage,
+            start_height.map(Bound::inclusive),
+            end_height.map(Bound::inclusive),
+            cosmwasm_std::Order::Ascending,
+        )
+        .collect::<StdResult<Vec<(u64, Vec<SlashEvent>)>>>()?
+        .into_iter()
+        .flat_map(|(height, events)| {
+            events.into_iter().map(move |event| SlashEventResponse {
+                height,
+                ratio: event.ratio,
+                amount: event.amount,
+                recipient: event.recipient.to_string(),
+                exchange_rate: event.exchange_rate,
+            })
+        })
+        .collect();
+
+    Ok(SlashEventsResponse { slash_events })
 }
 
 pub fn query_all_stakers(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<ListStakersResponse> {
@@ -315,22 +1299,93 @@ pub fn query_all_stakers(deps: Deps, start_after: Option<String>, limit: Option<
 
     let start_addr = valid_start_addr
         .as_ref()
-        .map(|addr| Bound::exclusive(addr));
+        .map(Bound::exclusive);
 
     let num_elements = match limit {
         Some(limit) => limit as usize,
         None => usize::MAX,
     };
 
+    let rate = exchange_rate(deps.storage)?;
     let stakers: Vec<StakerBalanceResponse> = STAKED_BALANCES
         .range(deps.storage, start_addr, None, cosmwasm_std::Order::Ascending)
         .take(num_elements)
         .filter_map(|item| item.ok()) // Gracefully handle potential errors.
-        .map(|(addr, balance)| StakerBalanceResponse {
+        .filter_map(|(addr, shares)| Some(StakerBalanceResponse {
             address: addr.to_string(),
-            balance,
-        })
+            balance: shares_to_tokens(shares, rate).ok()?,
+        }))
         .collect();
 
     Ok(ListStakersResponse { stakers })
-}
\ No newline at end of file
+}
+pub fn query_approvals(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ApprovalsResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+
+    let approvals = APPROVALS
+        .prefix(&owner)
+        .range(deps.storage, start_after.as_ref().map(Bound::exclusive), None, cosmwasm_std::Order::Ascending)
+        .filter(|item| !matches!(item, Ok((_, approval)) if approval.expires.is_expired(&env.block)))
+        .take(limit.map(|limit| limit as usize).unwrap_or(usize::MAX))
+        .map(|item| item.map(|(operator, approval)| ApprovalResponse {
+            operator: operator.to_string(),
+            expires: approval.expires,
+            permissions: approval.permissions,
+        }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ApprovalsResponse { approvals })
+}
+
+/// Tokens still being undelegated on chain are neither held nor delegated, so they show as
+/// a deficit until the chain unbonding is over. Rewards waiting for a harvest are not surplus.
+#[allow(deprecated)]
+fn reconcile(deps: Deps, env: &Env) -> StdResult<ReconcileResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let denom = config.staking_token.denom;
+    let ledger = BALANCE.load(deps.storage)?;
+    let unforwarded = Coins::try_from(UNFORWARDED_REWARDS.may_load(deps.storage)?.unwrap_or_default())?;
+
+    let mut balance = Uint128::zero();
+    let mut other_balances = vec![];
+    for held in deps.querier.query_all_balances(&env.contract.address)? {
+        if held.denom == denom {
+            balance = held.amount;
+            continue;
+        }
+        let surplus = held.amount.saturating_sub(unforwarded.amount_of(&held.denom));
+        if !surplus.is_zero() {
+            other_balances.push(coin(surplus.u128(), held.denom));
+        }
+    }
+
+    let delegated = match config.delegation {
+        None => Uint128::zero(),
+        Some(_) => deps.querier
+            .query_all_delegations(&env.contract.address)?
+            .into_iter()
+            .map(|delegation| delegation.amount.amount)
+            .sum(),
+    };
+
+    let held = balance.saturating_sub(unforwarded.amount_of(&denom)).checked_add(delegated)?;
+    Ok(ReconcileResponse {
+        denom,
+        ledger,
+        balance,
+        delegated,
+        surplus: held.saturating_sub(ledger),
+        deficit: ledger.saturating_sub(held),
+        other_balances,
+        unforwarded_rewards: unforwarded.into_vec(),
+    })
+}
@@ -1,5 +1,8 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
+use cw_controllers::HookError;
 use symphony_utils::duration::UnboundingDurationError;
+use symphony_interfaces::staking::Permission;
+use symphony_utils::migrate::MigrationError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -10,9 +13,15 @@ pub enum ContractError {
     #[error(transparent)]
     UnboundingDurationError(#[from] UnboundingDurationError),
 
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+
     #[error("No stake amount")]
     NoStakeAmount {},
 
+    #[error("Recipient amounts add up to {expected} but {received} was sent")]
+    StakeForFundsMismatch { expected: Uint128, received: Uint128 },
+
     #[error("Invalid denom to stake")]
     InvalidDenom {},
 
@@ -31,6 +40,71 @@ pub enum ContractError {
     #[error("Nothing to claim")]
     NothingToClaim {},
 
+    #[error("Invalid amount to rebond")]
+    InvalidRebondAmount {},
+
+    #[error("No lock tier with this duration")]
+    InvalidLockTier {},
+
+    #[error("Lock tier multiplier cannot be lower than 1")]
+    InvalidLockMultiplier {},
+
+    #[error("Too many locked positions")]
+    TooManyPositions {},
+
+    #[error("Amount exceeds the unlocked staked balance")]
+    StakeLocked {},
+
+    #[error("Slash ratio must be between 0 and 1")]
+    InvalidSlashRatio {},
+
+    #[error("Nothing to slash")]
+    NothingToSlash {},
+
+    #[error("Validator set must be non-empty, with distinct validators and non-zero weights")]
+    InvalidValidatorSet {},
+
+    #[error("Unknown validator {address}")]
+    UnknownValidator { address: String },
+
+    #[error("Staking token must be the chain bonded denom to delegate")]
+    InvalidDelegationDenom {},
+
+    #[error("Delegation is not enabled")]
+    DelegationDisabled {},
+
+    #[error("Cannot rebond while the staked tokens are delegated")]
+    RebondWhileDelegating {},
+
+    #[error("Cannot slash while the staked tokens are delegated")]
+    SlashWhileDelegating {},
+
+    #[error("No unstaked tokens are due for undelegation")]
+    NothingToUndelegate {},
+
+    #[error("No staking rewards to harvest")]
+    NothingToHarvest {},
+
+    #[error("Reply message id is unknown")]
+    UnknownReplyId {
+        id: u64,
+    },
+
+    #[error("No surplus to sweep")]
+    NothingToSweep {},
+
+    #[error("Sender is not approved to {permission} for this owner")]
+    NotApproved { permission: Permission },
+
+    #[error("Approval must grant at least one permission")]
+    NoPermissions {},
+
+    #[error("Approval expiration is already reached")]
+    ApprovalExpired {},
+
     #[error(transparent)]
     Ownership(#[from] cw_ownable::OwnershipError),
+
+    #[error(transparent)]
+    Hook(#[from] HookError),
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+use crate::error::ContractError;
+use crate::state::{Config, BALANCE, CLAIMS_BY_ADDR, CONFIG, STAKED_TOTAL, UNBONDING_TOTAL};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{DenomUnit, Env, Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::Item;
+use cw_utils::Duration;
+use symphony_interfaces::staking::RebondOrder;
+use symphony_utils::migrate::Migration;
+
+/// State transformers, by the version they migrate to.
+pub const MIGRATIONS: &[Migration<ContractError>] = &[("0.2.0", migrate_to_v0_2)];
+
+#[cw_serde]
+pub struct ConfigV0_1 {
+    pub staking_token: DenomUnit,
+    pub unstaking_duration: Option<Duration>,
+}
+
+pub const CONFIG_V0_1: Item<ConfigV0_1> = Item::new("config");
+
+/// Backfills the new config fields and the unbonding and balance ledgers. Balances and claims
+/// were kept in tokens, which are worth one share each before any slash.
+fn migrate_to_v0_2(storage: &mut dyn Storage, _env: &Env) -> Result<(), ContractError> {
+    let config = CONFIG_V0_1.load(storage)?;
+    CONFIG.save(storage, &Config {
+        staking_token: config.staking_token,
+        unstaking_duration: config.unstaking_duration,
+        rebond_order: RebondOrder::default(),
+        lock_tiers: vec![],
+        dao: None,
+        delegation: None,
+    })?;
+
+    let unbonding_total = CLAIMS_BY_ADDR
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, claims)| claims.iter().map(|claim| claim.amount).sum::<Uint128>()))
+        .collect::<StdResult<Vec<Uint128>>>()?
+        .into_iter()
+        .sum::<Uint128>();
+    UNBONDING_TOTAL.save(storage, &unbonding_total)?;
+
+    let staked_total = STAKED_TOTAL.may_load(storage)?.unwrap_or_default();
+    BALANCE.save(storage, &staked_total.checked_add(unbonding_total).map_err(StdError::from)?)?;
+
+    Ok(())
+}
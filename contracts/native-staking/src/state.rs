@@ -1,18 +1,51 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, DenomUnit, Uint128};
-use cw_controllers::Claims;
-use cw_storage_plus::{Item, SnapshotItem, SnapshotMap, Strategy};
-use cw_utils::Duration;
+use cosmwasm_std::{Addr, Coin, Decimal, DenomUnit, Timestamp, Uint128};
+use cw_controllers::{Claim, Claims, Hooks};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+use cw_utils::{Duration, Expiration};
+use symphony_interfaces::staking::{DelegationConfig, LockTier, Permission, RebondOrder};
 
 
 #[cw_serde]
 pub struct Config {
     pub staking_token: DenomUnit,
     pub unstaking_duration: Option<Duration>,
+    pub rebond_order: RebondOrder,
+    pub lock_tiers: Vec<LockTier>,
+    /// DAO the contract serves as voting module for.
+    pub dao: Option<Addr>,
+    /// Staked tokens are delegated to validators when set.
+    pub delegation: Option<DelegationConfig>,
+}
+
+/// Stake locked until `unlock_at`. It stays part of the staked balance.
+#[cw_serde]
+pub struct Position {
+    pub shares: Uint128,
+    pub lock_duration: Duration,
+    pub unlock_at: Expiration,
+    pub multiplier: Decimal,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Actions an operator may take for an owner until `expires`.
+#[cw_serde]
+pub struct Approval {
+    pub expires: Expiration,
+    pub permissions: Vec<Permission>,
+}
+
+#[cw_serde]
+pub struct SlashEvent {
+    pub ratio: Decimal,
+    pub amount: Uint128,
+    pub recipient: Addr,
+    /// Exchange rate after the slash.
+    pub exchange_rate: Decimal,
+}
+
+/// Staked shares per address, worth `EXCHANGE_RATE` tokens each.
 pub const STAKED_BALANCES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
     "staked_balances",
     "staked_balance__checkpoints",
@@ -20,6 +53,7 @@ pub const STAKED_BALANCES: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
     Strategy::EveryBlock,
 );
 
+/// Total staked shares.
 pub const STAKED_TOTAL: SnapshotItem<Uint128> = SnapshotItem::new(
     "total_staked",
     "total_staked__checkpoints",
@@ -27,8 +61,57 @@ pub const STAKED_TOTAL: SnapshotItem<Uint128> = SnapshotItem::new(
     Strategy::EveryBlock,
 );
 
+/// Tokens per share, lowered by every slash. One when never set.
+pub const EXCHANGE_RATE: SnapshotItem<Decimal> = SnapshotItem::new(
+    "exchange_rate",
+    "exchange_rate__checkpoints",
+    "exchange_rate__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Staked and unbonding tokens owed to stakers, whether held or delegated.
 pub const BALANCE: Item<Uint128> = Item::new("balance");
 
 pub const MAX_CLAIMS: u64 = 100;
 
-pub const CLAIMS: Claims = Claims::new("claims");
\ No newline at end of file
+const CLAIMS_KEY: &str = "claims";
+
+/// Pending claims, in shares so they are slashed along with the stake.
+pub const CLAIMS: Claims = Claims::new(CLAIMS_KEY);
+
+/// Raw view of `CLAIMS`, which does not allow reducing a pending claim.
+pub const CLAIMS_BY_ADDR: Map<&Addr, Vec<Claim>> = Map::new(CLAIMS_KEY);
+
+pub const HOOKS: Hooks = Hooks::new("hooks");
+
+pub const MAX_POSITIONS: u64 = 100;
+
+pub const POSITIONS: Map<(&Addr, u64), Position> = Map::new("positions");
+
+pub const POSITION_COUNT: Item<u64> = Item::new("position_count");
+
+/// Shares of all pending claims.
+pub const UNBONDING_TOTAL: Item<Uint128> = Item::new("unbonding_total");
+
+pub const SLASH_EVENTS: Map<u64, Vec<SlashEvent>> = Map::new("slash_events");
+
+/// Operator approvals, by owner and operator.
+pub const APPROVALS: Map<(&Addr, &Addr), Approval> = Map::new("approvals");
+
+/// Unbonding entries the chain allows per delegator and validator, 7 by default.
+pub const MAX_UNBONDING_ENTRIES: u64 = 7;
+
+/// Tokens unstaked but still delegated, undelegated together once `next_undelegation` is
+/// reached.
+#[cw_serde]
+#[derive(Default)]
+pub struct UndelegationBatch {
+    pub amount: Uint128,
+    pub next_undelegation: Timestamp,
+}
+
+pub const UNDELEGATION_BATCH: Item<UndelegationBatch> = Item::new("undelegation_batch");
+
+/// Staking rewards the chain withdrew into the contract when a delegation changed, forwarded
+/// by the next harvest.
+pub const UNFORWARDED_REWARDS: Item<Vec<Coin>> = Item::new("unforwarded_rewards");
@@ -1,11 +1,17 @@
 use crate::error::ContractError;
-use crate::state::Config;
-use cosmwasm_std::{coin, Addr, BlockInfo, Coin, DenomUnit, Empty, Uint128};
-use cw_controllers::ClaimsResponse;
-use cw_multi_test::{App, Contract, ContractWrapper, Executor};
-use cw_ownable::{Action, Ownership};
+use crate::migrations::{ConfigV0_1, CONFIG_V0_1};
+use crate::msg::MigrateMsg;
+use crate::state::{Config, CLAIMS, CONFIG, STAKED_BALANCES, STAKED_TOTAL};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+use cosmwasm_std::{coin, from_json, to_json_binary, Addr, BankMsg, Binary, BlockInfo, Coin, Decimal, DenomUnit, Deps, DepsMut, Empty, Env, Event, MessageInfo, Reply, Response, StdResult, SubMsg, SubMsgResponse, SubMsgResult, Uint128, Uint64, Validator, WasmMsg};
+use cw_controllers::{ClaimsResponse, HookError, HooksResponse};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor, StakingInfo};
+use cw_ownable::{Action, Ownership, OwnershipError};
+use cw_storage_plus::Item;
 use cw_utils::{Duration, Expiration};
-use symphony_interfaces::staking::{ExecuteMsg, InstantiateMsg, ListStakersResponse, QueryMsg, StakedBalanceAtHeightResponse, TotalStakedAtHeightResponse};
+use symphony_utils::migrate::MigrationError;
+use symphony_interfaces::staking::{ApprovalResponse, ApprovalsResponse, DelegationConfig, ExecuteMsg, InfoResponse, InstantiateMsg, ListStakersResponse, LockTier, LockTiersResponse, Permission, PositionResponse, PositionsResponse, QueryMsg, RebondOrder, ReconcileResponse, RewardsExecuteMsg, SlashEventResponse, SlashEventsResponse, StakeChangedExecuteMsg, StakeChangedHookMsg, StakedBalanceAtHeightResponse, TotalPowerAtHeightResponse, TotalStakedAtHeightResponse, ValidatorWeight, VotingPowerAtHeightResponse};
 
 const OWNER: &str = "owner";
 const TIME_BETWEEN_BLOCKS: u64 = 5;
@@ -38,20 +44,138 @@ pub fn native_staking_contract() -> Box<dyn Contract<Empty>> {
         crate::contract::instantiate,
         crate::contract::query,
     )
+        .with_reply(crate::contract::reply)
         .with_migrate(crate::contract::migrate);
     Box::new(contract)
 }
 
+const LAST_HOOK: Item<StakeChangedHookMsg> = Item::new("last_hook");
+
+// records the last hook received so tests can inspect it
+pub fn hook_receiver_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps: DepsMut, _env: Env, _info: MessageInfo, msg: StakeChangedExecuteMsg| -> StdResult<Response> {
+            if let StakeChangedExecuteMsg::StakeChangedHook(hook) = msg {
+                LAST_HOOK.save(deps.storage, &hook)?;
+            }
+            Ok(Response::new())
+        },
+        |_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |deps: Deps, _env: Env, _msg: Empty| -> StdResult<Binary> {
+            to_json_binary(&LAST_HOOK.load(deps.storage)?)
+        },
+    );
+    Box::new(contract)
+}
+
+fn instantiate_hook_receiver(app: &mut App) -> Addr {
+    let code_id = app.store_code(hook_receiver_contract());
+    app.instantiate_contract(
+        code_id,
+        app.api().addr_make(OWNER),
+        &Empty {},
+        &[],
+        "hook_receiver",
+        None,
+    )
+        .unwrap()
+}
+
+// accepts harvested rewards without doing anything with them
+pub fn rewards_receiver_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: RewardsExecuteMsg| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_deps: Deps, _env: Env, _msg: Empty| -> StdResult<Binary> {
+            to_json_binary(&Empty {})
+        },
+    );
+    Box::new(contract)
+}
+
+#[cw_serde]
+struct LegacyInstantiateMsg {
+    contract: String,
+    version: String,
+    stakers: Vec<(Addr, Uint128)>,
+    claim: (Addr, Uint128),
+}
+
+// writes the state of the first release, as deployed before the current code
+pub fn legacy_staking_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |deps: DepsMut, env: Env, info: MessageInfo, msg: LegacyInstantiateMsg| -> StdResult<Response> {
+            cw2::set_contract_version(deps.storage, msg.contract, msg.version)?;
+            cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
+            CONFIG_V0_1.save(deps.storage, &ConfigV0_1 {
+                staking_token: DenomUnit {
+                    denom: "ustake".to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+                unstaking_duration: Some(Duration::Time(100)),
+            })?;
+
+            let mut total = Uint128::zero();
+            for (staker, amount) in msg.stakers {
+                STAKED_BALANCES.save(deps.storage, &staker, &amount, env.block.height)?;
+                total += amount;
+            }
+            STAKED_TOTAL.save(deps.storage, &total, env.block.height)?;
+            let (claimant, amount) = msg.claim;
+            CLAIMS.create_claim(deps.storage, &claimant, amount, Duration::Time(100).after(&env.block))?;
+            Ok(Response::new())
+        },
+        |_deps: Deps, _env: Env, _msg: Empty| -> StdResult<Binary> {
+            to_json_binary(&Empty {})
+        },
+    );
+    Box::new(contract)
+}
+
+fn instantiate_legacy_staking(app: &mut App, contract: &str, version: &str) -> Addr {
+    let code_id = app.store_code(legacy_staking_contract());
+    let admin = app.api().addr_make(OWNER);
+    app.instantiate_contract(
+        code_id,
+        admin.clone(),
+        &LegacyInstantiateMsg {
+            contract: contract.to_string(),
+            version: version.to_string(),
+            stakers: vec![
+                (app.api().addr_make("staker1"), Uint128::new(700)),
+                (app.api().addr_make("staker2"), Uint128::new(300)),
+            ],
+            claim: (app.api().addr_make("staker2"), Uint128::new(300)),
+        },
+        &[],
+        "legacy staking",
+        Some(admin.to_string()),
+    )
+        .unwrap()
+}
+
 fn instantiate_staking(app: &mut App, owner: Option<String>, native_token: &DenomUnit, unbounding_duration: &Option<Duration>) -> Addr {
     let staking_code_id = app.store_code(native_staking_contract());
     let msg = InstantiateMsg {
         owner,
         denom_unit: native_token.clone(),
-        unbonding_period: unbounding_duration.clone(),
+        unbonding_period: *unbounding_duration,
+        hooks: None,
+        dao: None,
     };
     app.instantiate_contract(
         staking_code_id,
-        app.api().addr_make(OWNER).into(),
+        app.api().addr_make(OWNER),
         &msg,
         &[],
         "staking",
@@ -62,7 +186,7 @@ fn instantiate_staking(app: &mut App, owner: Option<String>, native_token: &Deno
 
 #[test]
 pub fn native_staking_instantiate() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
         exponent: 6,
@@ -71,7 +195,7 @@ pub fn native_staking_instantiate() {
     let unbounding_duration = Some(Duration::Time(100));
     let owner_address = app.api().addr_make(OWNER);
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         Some(owner_address.into()),
         &native_token,
         &unbounding_duration
@@ -86,7 +210,7 @@ pub fn native_staking_instantiate() {
 
 #[test]
 pub fn native_staking_instantiate_without_explicit_owner() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
         exponent: 6,
@@ -94,7 +218,7 @@ pub fn native_staking_instantiate_without_explicit_owner() {
     };
     let unbounding_duration = Some(Duration::Time(100));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
@@ -109,7 +233,7 @@ pub fn native_staking_instantiate_without_explicit_owner() {
 
 #[test]
 pub fn update_ownership() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
@@ -118,7 +242,7 @@ pub fn update_ownership() {
     };
     let unbounding_duration = Some(Duration::Time(100));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
@@ -143,7 +267,7 @@ pub fn update_ownership() {
 
 #[test]
 pub fn execute_stake_should_fail_if_no_funds() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
@@ -152,7 +276,7 @@ pub fn execute_stake_should_fail_if_no_funds() {
     };
     let unbounding_duration = Some(Duration::Time(100));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
@@ -165,7 +289,7 @@ pub fn execute_stake_should_fail_if_no_funds() {
 
 #[test]
 pub fn execute_stake_should_fail_if_more_than_one_coin_sent() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
@@ -174,15 +298,15 @@ pub fn execute_stake_should_fail_if_more_than_one_coin_sent() {
     };
     let unbounding_duration = Some(Duration::Time(100));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
     );
 
     // add funds to sender address
-    mint_native(&mut app, sender.to_string(), "ustake".to_string(), 100_000u128);
-    mint_native(&mut app, sender.to_string(), "utoken".to_string(), 100_000u128);
+    mint_native(app, sender.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, sender.to_string(), "utoken".to_string(), 100_000u128);
 
     let msg = ExecuteMsg::Stake {};
     let err = app.execute_contract(sender, staking_contract, &msg, &[
@@ -200,7 +324,7 @@ pub fn execute_stake_should_fail_if_more_than_one_coin_sent() {
 
 #[test]
 pub fn execute_stake_should_succeed() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
@@ -209,14 +333,14 @@ pub fn execute_stake_should_succeed() {
     };
     let unbounding_duration = Some(Duration::Time(100));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
     );
 
     // add funds to sender address
-    mint_native(&mut app, sender.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, sender.to_string(), "ustake".to_string(), 100_000u128);
 
     let msg = ExecuteMsg::Stake {};
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[
@@ -242,7 +366,7 @@ pub fn execute_stake_should_succeed() {
     assert_eq!(balance.amount, Uint128::from(100u128));
 
 
-    next_block(&mut app);
+    next_block(app);
 
     // query staked balance
     let staked_balance: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
@@ -262,7 +386,7 @@ pub fn execute_stake_should_succeed() {
 
 #[test]
 pub fn execute_unstake_should_fail_if_no_staked_amount() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
@@ -271,7 +395,7 @@ pub fn execute_unstake_should_fail_if_no_staked_amount() {
     };
     let unbounding_duration = Some(Duration::Time(100));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
@@ -279,6 +403,7 @@ pub fn execute_unstake_should_fail_if_no_staked_amount() {
 
     let msg = ExecuteMsg::Unstake {
         amount: Uint128::from(100u128),
+        owner: None,
     };
     let err = app.execute_contract(sender, staking_contract, &msg, &[]).unwrap_err();
     assert_eq!(err.root_cause().to_string(), ContractError::NoUnstakeAmount {}.to_string());
@@ -286,7 +411,7 @@ pub fn execute_unstake_should_fail_if_no_staked_amount() {
 
 #[test]
 pub fn execute_unstake_should_fail_if_amount_is_bigger_than_total_staked() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
@@ -295,14 +420,14 @@ pub fn execute_unstake_should_fail_if_amount_is_bigger_than_total_staked() {
     };
     let unbounding_duration = None;
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
     );
 
     // add funds to sender address
-    mint_native(&mut app, sender.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, sender.to_string(), "ustake".to_string(), 100_000u128);
 
     let msg = ExecuteMsg::Stake {};
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[
@@ -312,10 +437,11 @@ pub fn execute_unstake_should_fail_if_amount_is_bigger_than_total_staked() {
         }
     ]);
 
-    next_block(&mut app);
+    next_block(app);
 
     let msg = ExecuteMsg::Unstake {
         amount: Uint128::from(200u128),
+        owner: None,
     };
     let err = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[]).unwrap_err();
     assert_eq!(err.root_cause().to_string(), ContractError::InvalidUnstakeAmount {}.to_string());
@@ -323,7 +449,7 @@ pub fn execute_unstake_should_fail_if_amount_is_bigger_than_total_staked() {
 
 #[test]
 pub fn execute_unstake_should_fail_if_amount_is_bigger_than_user_staked() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let other_user = app.api().addr_make("other_user");
     let native_token = DenomUnit {
@@ -333,15 +459,15 @@ pub fn execute_unstake_should_fail_if_amount_is_bigger_than_user_staked() {
     };
     let unbounding_duration = None;
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
     );
 
     // add funds to sender address
-    mint_native(&mut app, sender.to_string(), "ustake".to_string(), 100_000u128);
-    mint_native(&mut app, other_user.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, sender.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, other_user.to_string(), "ustake".to_string(), 100_000u128);
 
     let msg = ExecuteMsg::Stake {};
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[
@@ -359,10 +485,11 @@ pub fn execute_unstake_should_fail_if_amount_is_bigger_than_user_staked() {
         }
     ]);
 
-    next_block(&mut app);
+    next_block(app);
 
     let msg = ExecuteMsg::Unstake {
         amount: Uint128::from(200u128),
+        owner: None,
     };
     let err = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[]).unwrap_err();
     assert_eq!(err.root_cause().to_string(), ContractError::InvalidUnstakeAmount {}.to_string());
@@ -370,7 +497,7 @@ pub fn execute_unstake_should_fail_if_amount_is_bigger_than_user_staked() {
 
 #[test]
 pub fn execute_unstake_should_succeed() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let other_user = app.api().addr_make("other_user");
     let native_token = DenomUnit {
@@ -380,15 +507,15 @@ pub fn execute_unstake_should_succeed() {
     };
     let unbounding_duration = None;
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
     );
 
     // add funds to sender address
-    mint_native(&mut app, sender.to_string(), "ustake".to_string(), 100_000u128);
-    mint_native(&mut app, other_user.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, sender.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, other_user.to_string(), "ustake".to_string(), 100_000u128);
 
     let msg = ExecuteMsg::Stake {};
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[
@@ -406,14 +533,15 @@ pub fn execute_unstake_should_succeed() {
         }
     ]);
 
-    next_block(&mut app);
+    next_block(app);
 
     let msg = ExecuteMsg::Unstake {
         amount: Uint128::from(100u128),
+        owner: None,
     };
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[]);
 
-    next_block(&mut app);
+    next_block(app);
     // query staked balance for owner
     let staked_balance: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
         address: sender.to_string(),
@@ -441,7 +569,7 @@ pub fn execute_unstake_should_succeed() {
 
 #[test]
 pub fn execute_unstake_with_unbound_period_should_succeed() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
@@ -450,14 +578,14 @@ pub fn execute_unstake_with_unbound_period_should_succeed() {
     };
     let unbounding_duration = Some(Duration::Time(5));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
     );
 
     // add funds to sender address
-    mint_native(&mut app, sender.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, sender.to_string(), "ustake".to_string(), 100_000u128);
 
     let msg = ExecuteMsg::Stake {};
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[
@@ -467,14 +595,15 @@ pub fn execute_unstake_with_unbound_period_should_succeed() {
         }
     ]);
 
-    next_block(&mut app);
+    next_block(app);
 
     let msg = ExecuteMsg::Unstake {
         amount: Uint128::from(100u128),
+        owner: None,
     };
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[]);
 
-    next_block(&mut app);
+    next_block(app);
     // query staked balance for owner
     let staked_balance: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
         address: sender.to_string(),
@@ -506,7 +635,7 @@ pub fn execute_unstake_with_unbound_period_should_succeed() {
     let _ = app.execute_contract(
         sender.clone(),
         staking_contract.clone(),
-        &ExecuteMsg::Claim {},
+        &ExecuteMsg::Claim { owner: None },
         &[]
     );
 
@@ -521,7 +650,7 @@ pub fn execute_unstake_with_unbound_period_should_succeed() {
 
 #[test]
 pub fn execute_unstake_with_unbound_period_should_fail_before_time() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let sender = app.api().addr_make(OWNER);
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
@@ -530,14 +659,14 @@ pub fn execute_unstake_with_unbound_period_should_fail_before_time() {
     };
     let unbounding_duration = Some(Duration::Time(15));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
     );
 
     // add funds to sender address
-    mint_native(&mut app, sender.to_string(), "ustake".to_string(), 100_000u128);
+    mint_native(app, sender.to_string(), "ustake".to_string(), 100_000u128);
 
     let msg = ExecuteMsg::Stake {};
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[
@@ -547,14 +676,15 @@ pub fn execute_unstake_with_unbound_period_should_fail_before_time() {
         }
     ]);
 
-    next_block(&mut app);
+    next_block(app);
 
     let msg = ExecuteMsg::Unstake {
         amount: Uint128::from(100u128),
+        owner: None,
     };
     let _ = app.execute_contract(sender.clone(), staking_contract.clone(), &msg, &[]);
 
-    next_block(&mut app);
+    next_block(app);
     // query staked balance for owner
     let staked_balance: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
         address: sender.to_string(),
@@ -586,7 +716,7 @@ pub fn execute_unstake_with_unbound_period_should_fail_before_time() {
     let err = app.execute_contract(
         sender.clone(),
         staking_contract.clone(),
-        &ExecuteMsg::Claim {},
+        &ExecuteMsg::Claim { owner: None },
         &[]
     ).unwrap_err();
 
@@ -603,7 +733,7 @@ pub fn execute_unstake_with_unbound_period_should_fail_before_time() {
 
 #[test]
 pub fn query_list_stakers_should_return_all_stakers() {
-    let mut app = &mut mock_app();
+    let app = &mut mock_app();
     let native_token = DenomUnit {
         denom: "ustake".to_string(),
         exponent: 6,
@@ -611,7 +741,7 @@ pub fn query_list_stakers_should_return_all_stakers() {
     };
     let unbounding_duration = Some(Duration::Time(15));
     let staking_contract = instantiate_staking(
-        &mut app,
+        app,
         None,
         &native_token,
         &unbounding_duration
@@ -621,7 +751,7 @@ pub fn query_list_stakers_should_return_all_stakers() {
     for i in 1..101 {
         let user_name = format!("user_{}", i);
         let user = app.api().addr_make(&user_name);
-        mint_native(&mut app, user.to_string(), "ustake".to_string(), 100_000u128);
+        mint_native(app, user.to_string(), "ustake".to_string(), 100_000u128);
 
         let msg = ExecuteMsg::Stake {};
         let _ = app.execute_contract(user.clone(), staking_contract.clone(), &msg, &[
@@ -631,7 +761,7 @@ pub fn query_list_stakers_should_return_all_stakers() {
             }
         ]);
     }
-    next_block(&mut app); // move to next block to update staked balance
+    next_block(app); // move to next block to update staked balance
 
     let first_response: ListStakersResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::ListStakers {
         start_after: None,
@@ -648,7 +778,1055 @@ pub fn query_list_stakers_should_return_all_stakers() {
     assert_eq!(last_response.stakers.len(), 80);
 
     // the intersection should be empty
-    assert_eq!(first_response.stakers.iter().any(|x| last_response.stakers.contains(x)), false);
+    assert!(!first_response.stakers.iter().any(|x| last_response.stakers.contains(x)));
     // the union should be equal to the total number of stakers
     assert_eq!(first_response.stakers.len() + last_response.stakers.len(), 100);
-}
\ No newline at end of file
+}
+#[test]
+pub fn add_and_remove_hooks() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let other_user = app.api().addr_make("other_user");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(
+        app,
+        None,
+        &native_token,
+        &None
+    );
+    let hook = app.api().addr_make("hook");
+
+    let msg = ExecuteMsg::AddHook { addr: hook.to_string() };
+    let err = app.execute_contract(other_user, staking_contract.clone(), &msg, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), OwnershipError::NotOwner.to_string());
+
+    app.execute_contract(owner.clone(), staking_contract.clone(), &msg, &[]).unwrap();
+    let err = app.execute_contract(owner.clone(), staking_contract.clone(), &msg, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), HookError::HookAlreadyRegistered {}.to_string());
+
+    let hooks: HooksResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Hooks {}).unwrap();
+    assert_eq!(hooks.hooks, vec![hook.to_string()]);
+
+    let msg = ExecuteMsg::RemoveHook { addr: hook.to_string() };
+    app.execute_contract(owner.clone(), staking_contract.clone(), &msg, &[]).unwrap();
+    let err = app.execute_contract(owner, staking_contract.clone(), &msg, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), HookError::HookNotRegistered {}.to_string());
+
+    let hooks: HooksResponse = app.wrap().query_wasm_smart(staking_contract, &QueryMsg::Hooks {}).unwrap();
+    assert!(hooks.hooks.is_empty());
+}
+
+#[test]
+pub fn stake_and_unstake_should_notify_hooks() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let sender = app.api().addr_make("staker");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(
+        app,
+        None,
+        &native_token,
+        &Some(Duration::Time(10))
+    );
+    let receiver = instantiate_hook_receiver(app);
+
+    app.execute_contract(
+        owner,
+        staking_contract.clone(),
+        &ExecuteMsg::AddHook { addr: receiver.to_string() },
+        &[]
+    ).unwrap();
+
+    mint_native(app, sender.to_string(), "ustake".to_string(), 1_000u128);
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(300, "ustake")
+    ]).unwrap();
+
+    let hook: StakeChangedHookMsg = app.wrap().query_wasm_smart(receiver.clone(), &Empty {}).unwrap();
+    assert_eq!(hook, StakeChangedHookMsg {
+        addr: sender.to_string(),
+        old_balance: Uint128::zero(),
+        new_balance: Uint128::new(300),
+        denom: "ustake".to_string(),
+    });
+
+    next_block(app);
+
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(100),
+        owner: None,
+    }, &[]).unwrap();
+
+    let hook: StakeChangedHookMsg = app.wrap().query_wasm_smart(receiver, &Empty {}).unwrap();
+    assert_eq!(hook, StakeChangedHookMsg {
+        addr: sender.to_string(),
+        old_balance: Uint128::new(300),
+        new_balance: Uint128::new(200),
+        denom: "ustake".to_string(),
+    });
+}
+
+#[test]
+pub fn rebond_should_restake_pending_claims() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let sender = app.api().addr_make("staker");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(
+        app,
+        None,
+        &native_token,
+        &Some(Duration::Time(100))
+    );
+    let receiver = instantiate_hook_receiver(app);
+
+    app.execute_contract(
+        owner.clone(),
+        staking_contract.clone(),
+        &ExecuteMsg::AddHook { addr: receiver.to_string() },
+        &[]
+    ).unwrap();
+
+    mint_native(app, sender.to_string(), "ustake".to_string(), 1_000u128);
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(1_000, "ustake")
+    ]).unwrap();
+
+    for amount in [100u128, 200, 300] {
+        next_block(app);
+        app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+            amount: Uint128::new(amount),
+            owner: None,
+        }, &[]).unwrap();
+    }
+    next_block(app);
+
+    let claim_amounts = |app: &App| -> Vec<Uint128> {
+        let claims: ClaimsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Claims {
+            address: sender.to_string(),
+        }).unwrap();
+        claims.claims.into_iter().map(|claim| claim.amount).collect()
+    };
+
+    // cannot rebond more than what is unbonding
+    let err = app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Rebond {
+        amount: Uint128::new(601),
+        owner: None,
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidRebondAmount {});
+
+    // newest claims are rebonded first by default
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Rebond {
+        amount: Uint128::new(350),
+        owner: None,
+    }, &[]).unwrap();
+    assert_eq!(claim_amounts(app), vec![Uint128::new(100), Uint128::new(150)]);
+
+    let hook: StakeChangedHookMsg = app.wrap().query_wasm_smart(receiver, &Empty {}).unwrap();
+    assert_eq!(hook, StakeChangedHookMsg {
+        addr: sender.to_string(),
+        old_balance: Uint128::new(400),
+        new_balance: Uint128::new(750),
+        denom: "ustake".to_string(),
+    });
+
+    app.execute_contract(owner, staking_contract.clone(), &ExecuteMsg::UpdateSettings {
+        rebond_order: Some(RebondOrder::OldestFirst),
+        lock_tiers: None,
+        dao: None,
+        delegation: None,
+    }, &[]).unwrap();
+
+    // the unbonding period is left as it was
+    let config: Config = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Config {}).unwrap();
+    assert_eq!(config.unstaking_duration, Some(Duration::Time(100)));
+    assert_eq!(config.rebond_order, RebondOrder::OldestFirst);
+
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Rebond {
+        amount: Uint128::new(150),
+        owner: None,
+    }, &[]).unwrap();
+    assert_eq!(claim_amounts(app), vec![Uint128::new(100)]);
+    next_block(app);
+
+    let staked_balance: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
+        address: sender.to_string(),
+        height: None,
+    }).unwrap();
+    assert_eq!(staked_balance.balance, Uint128::new(900));
+    let total: TotalStakedAtHeightResponse = app.wrap().query_wasm_smart(staking_contract, &QueryMsg::TotalStakedAtHeight {
+        height: None,
+    }).unwrap();
+    assert_eq!(total.total, Uint128::new(900));
+}
+
+#[test]
+pub fn locked_positions_should_not_unstake_before_expiry() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let sender = app.api().addr_make("staker");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(
+        app,
+        None,
+        &native_token,
+        &None
+    );
+
+    let lock_tier = LockTier {
+        duration: Duration::Time(100),
+        multiplier: Decimal::percent(150),
+    };
+    let update_lock_tiers = |app: &mut App, lock_tiers: Vec<LockTier>| {
+        app.execute_contract(owner.clone(), staking_contract.clone(), &ExecuteMsg::UpdateSettings {
+            rebond_order: None,
+            lock_tiers: Some(lock_tiers),
+            dao: None,
+            delegation: None,
+        }, &[])
+    };
+
+    let err = update_lock_tiers(app, vec![LockTier {
+        duration: Duration::Time(100),
+        multiplier: Decimal::percent(50),
+    }]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidLockMultiplier {});
+
+    update_lock_tiers(app, vec![lock_tier.clone()]).unwrap();
+    let lock_tiers: LockTiersResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::LockTiers {}).unwrap();
+    assert_eq!(lock_tiers.lock_tiers, vec![lock_tier]);
+
+    mint_native(app, sender.to_string(), "ustake".to_string(), 300u128);
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(100, "ustake")
+    ]).unwrap();
+
+    let err = app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::StakeLocked {
+        duration: Duration::Time(50),
+    }, &[coin(200, "ustake")]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidLockTier {});
+
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::StakeLocked {
+        duration: Duration::Time(100),
+    }, &[coin(200, "ustake")]).unwrap();
+
+    let positions: PositionsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Positions {
+        address: sender.to_string(),
+    }).unwrap();
+    assert_eq!(positions.positions, vec![PositionResponse {
+        id: 1,
+        amount: Uint128::new(200),
+        lock_duration: Duration::Time(100),
+        unlock_at: Expiration::AtTime(app.block_info().time.plus_seconds(100)),
+        multiplier: Decimal::percent(150),
+    }]);
+    next_block(app);
+
+    // locked stake is part of the staked balance
+    let staked_balance: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
+        address: sender.to_string(),
+        height: None,
+    }).unwrap();
+    assert_eq!(staked_balance.balance, Uint128::new(300));
+
+    // only the flexible stake can be unstaked before the lock expires
+    let err = app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(150),
+        owner: None,
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::StakeLocked {});
+
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(100),
+        owner: None,
+    }, &[]).unwrap();
+
+    for _ in 0..20 {
+        next_block(app);
+    }
+
+    let positions: PositionsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Positions {
+        address: sender.to_string(),
+    }).unwrap();
+    assert!(positions.positions.is_empty());
+
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(200),
+        owner: None,
+    }, &[]).unwrap();
+
+    let balance = app.wrap().query_balance(sender, "ustake").unwrap();
+    assert_eq!(balance.amount, Uint128::new(300));
+}
+
+#[test]
+pub fn voting_module_queries() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let dao = app.api().addr_make("dao");
+    let sender = app.api().addr_make("staker");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(
+        app,
+        None,
+        &native_token,
+        &None
+    );
+
+    // no DAO is bound by default
+    let err = app.wrap().query_wasm_smart::<Addr>(staking_contract.clone(), &QueryMsg::Dao {}).unwrap_err();
+    assert!(err.to_string().contains("not found"));
+
+    app.execute_contract(owner, staking_contract.clone(), &ExecuteMsg::UpdateSettings {
+        rebond_order: None,
+        lock_tiers: None,
+        dao: Some(dao.to_string()),
+        delegation: None,
+    }, &[]).unwrap();
+
+    let bound_dao: Addr = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Dao {}).unwrap();
+    assert_eq!(bound_dao, dao);
+
+    let info: InfoResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Info {}).unwrap();
+    assert_eq!(info.info.contract, "crates.io:symphony-native-staking");
+
+    mint_native(app, sender.to_string(), "ustake".to_string(), 100u128);
+    app.execute_contract(sender.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(100, "ustake")
+    ]).unwrap();
+    let stake_height = app.block_info().height;
+    next_block(app);
+
+    let power: VotingPowerAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::VotingPowerAtHeight {
+        address: sender.to_string(),
+        height: None,
+    }).unwrap();
+    assert_eq!(power, VotingPowerAtHeightResponse { power: Uint128::new(100), height: app.block_info().height });
+
+    // power is snapshotted at the start of each block
+    let power: VotingPowerAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::VotingPowerAtHeight {
+        address: sender.to_string(),
+        height: Some(stake_height),
+    }).unwrap();
+    assert!(power.power.is_zero());
+
+    let total: TotalPowerAtHeightResponse = app.wrap().query_wasm_smart(staking_contract, &QueryMsg::TotalPowerAtHeight {
+        height: None,
+    }).unwrap();
+    assert_eq!(total.power, Uint128::new(100));
+}
+
+#[test]
+pub fn slash_should_scale_balances_and_claims() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let staker1 = app.api().addr_make("staker1");
+    let staker2 = app.api().addr_make("staker2");
+    let treasury = app.api().addr_make("treasury");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(
+        app,
+        None,
+        &native_token,
+        &Some(Duration::Time(100))
+    );
+
+    for staker in [&staker1, &staker2] {
+        mint_native(app, staker.to_string(), "ustake".to_string(), 1_000u128);
+        app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+            coin(1_000, "ustake")
+        ]).unwrap();
+    }
+    next_block(app);
+    app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(400),
+        owner: None,
+    }, &[]).unwrap();
+    next_block(app);
+    let height_before_slash = app.block_info().height;
+
+    let err = app.execute_contract(staker1.clone(), staking_contract.clone(), &ExecuteMsg::Slash {
+        ratio: Decimal::percent(10),
+        recipient: treasury.to_string(),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Ownership(OwnershipError::NotOwner));
+
+    let err = app.execute_contract(owner.clone(), staking_contract.clone(), &ExecuteMsg::Slash {
+        ratio: Decimal::one(),
+        recipient: treasury.to_string(),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::InvalidSlashRatio {});
+
+    // staked and unbonding tokens are both slashed
+    app.execute_contract(owner, staking_contract.clone(), &ExecuteMsg::Slash {
+        ratio: Decimal::percent(10),
+        recipient: treasury.to_string(),
+    }, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&treasury, "ustake").unwrap().amount, Uint128::new(200));
+    next_block(app);
+
+    let balance = |app: &App, address: &Addr, height: Option<u64>| -> Uint128 {
+        let response: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
+            address: address.to_string(),
+            height,
+        }).unwrap();
+        response.balance
+    };
+    assert_eq!(balance(app, &staker1, None), Uint128::new(900));
+    assert_eq!(balance(app, &staker2, None), Uint128::new(540));
+    assert_eq!(balance(app, &staker1, Some(height_before_slash)), Uint128::new(1_000));
+
+    let total: TotalStakedAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::TotalStakedAtHeight {
+        height: None,
+    }).unwrap();
+    assert_eq!(total.total, Uint128::new(1_440));
+
+    let claims: ClaimsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Claims {
+        address: staker2.to_string(),
+    }).unwrap();
+    assert_eq!(claims.claims[0].amount, Uint128::new(360));
+
+    let slash_events: SlashEventsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::SlashEvents {
+        start_height: Some(height_before_slash),
+        end_height: None,
+    }).unwrap();
+    assert_eq!(slash_events.slash_events, vec![SlashEventResponse {
+        height: height_before_slash,
+        ratio: Decimal::percent(10),
+        amount: Uint128::new(200),
+        recipient: treasury.to_string(),
+        exchange_rate: Decimal::percent(90),
+    }]);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Claim { owner: None }, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&staker2, "ustake").unwrap().amount, Uint128::new(360));
+
+    // what is left backs the remaining stake exactly
+    let balance_left = app.wrap().query_balance(&staking_contract, "ustake").unwrap().amount;
+    assert_eq!(balance_left, Uint128::new(1_440));
+}
+
+#[test]
+pub fn delegation_should_delegate_stake_and_harvest_rewards() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let staker1 = app.api().addr_make("staker1");
+    let staker2 = app.api().addr_make("staker2");
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router.staking.setup(storage, StakingInfo {
+            bonded_denom: "ustake".to_string(),
+            unbonding_time: 60,
+            apr: Decimal::percent(10),
+        }).unwrap();
+        for validator in ["validator1", "validator2"] {
+            router.staking.add_validator(api, storage, &block, Validator::create(
+                validator.to_string(),
+                Decimal::zero(),
+                Decimal::one(),
+                Decimal::one(),
+            )).unwrap();
+        }
+    });
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(app, None, &native_token, &None);
+    let rewards_code_id = app.store_code(rewards_receiver_contract());
+    let rewards_contract = app.instantiate_contract(rewards_code_id, owner.clone(), &Empty {}, &[], "rewards", None).unwrap();
+
+    mint_native(app, staker1.to_string(), "ustake".to_string(), 1_000u128);
+    mint_native(app, staker2.to_string(), "ustake".to_string(), 400u128);
+    app.execute_contract(staker1.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(1_000, "ustake")
+    ]).unwrap();
+
+    let update_config = |validators: Vec<(&str, u64)>| ExecuteMsg::UpdateSettings {
+        rebond_order: None,
+        lock_tiers: None,
+        dao: None,
+        delegation: Some(DelegationConfig {
+            validators: validators.into_iter().map(|(address, weight)| ValidatorWeight {
+                address: address.to_string(),
+                weight: Uint64::new(weight),
+            }).collect(),
+            unbonding_time: 60,
+            rewards_contract: rewards_contract.to_string(),
+        }),
+    };
+
+    let err = app.execute_contract(owner.clone(), staking_contract.clone(), &update_config(vec![
+        ("validator1", 3),
+        ("unknown", 1),
+    ]), &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::UnknownValidator { address: "unknown".to_string() });
+
+    // tokens staked before enabling delegation are delegated too
+    app.execute_contract(owner.clone(), staking_contract.clone(), &update_config(vec![
+        ("validator1", 3),
+        ("validator2", 1),
+    ]), &[]).unwrap();
+    app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(400, "ustake")
+    ]).unwrap();
+
+    let delegated = |app: &App, validator: &str| -> Uint128 {
+        app.wrap().query_delegation(&staking_contract, validator).unwrap()
+            .map(|delegation| delegation.amount.amount)
+            .unwrap_or_default()
+    };
+    assert_eq!(delegated(app, "validator1"), Uint128::new(1_050));
+    assert_eq!(delegated(app, "validator2"), Uint128::new(350));
+    assert_eq!(app.wrap().query_balance(&staking_contract, "ustake").unwrap().amount, Uint128::zero());
+
+    // the slashed tokens would be taken from the bank balance, which holds none of the stake
+    let err = app.execute_contract(owner.clone(), staking_contract.clone(), &ExecuteMsg::Slash {
+        ratio: Decimal::percent(10),
+        recipient: owner.to_string(),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::SlashWhileDelegating {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(60 * 60 * 24 * 365));
+    let pending_rewards: Uint128 = ["validator1", "validator2"]
+        .into_iter()
+        .map(|validator| {
+            app.wrap().query_delegation(&staking_contract, validator).unwrap().unwrap()
+                .accumulated_rewards
+                .into_iter()
+                .map(|reward| reward.amount)
+                .sum::<Uint128>()
+        })
+        .sum();
+    assert!(!pending_rewards.is_zero());
+
+    app.execute_contract(staker1.clone(), staking_contract.clone(), &ExecuteMsg::Harvest {}, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&rewards_contract, "ustake").unwrap().amount, pending_rewards);
+    assert_eq!(app.wrap().query_balance(&staking_contract, "ustake").unwrap().amount, Uint128::zero());
+
+    // unstaking undelegates and waits for the chain unbonding
+    app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(400),
+        owner: None,
+    }, &[]).unwrap();
+    assert_eq!(delegated(app, "validator1"), Uint128::new(750));
+    assert_eq!(delegated(app, "validator2"), Uint128::new(250));
+
+    let err = app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Rebond {
+        amount: Uint128::new(400),
+        owner: None,
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::RebondWhileDelegating {});
+
+    let err = app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Claim { owner: None }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToClaim {});
+
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+    app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Claim { owner: None }, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&staker2, "ustake").unwrap().amount, Uint128::new(400));
+}
+
+// delegating contract on mock dependencies, whose replies can be given chain events
+fn instantiate_delegating_staking(mut deps: DepsMut, env: &Env, owner: &Addr, rewards_contract: &Addr) {
+    crate::contract::instantiate(deps.branch(), env.clone(), message_info(owner, &[]), InstantiateMsg {
+        owner: None,
+        denom_unit: DenomUnit {
+            denom: "ustake".to_string(),
+            exponent: 6,
+            aliases: vec![],
+        },
+        unbonding_period: None,
+        hooks: None,
+        dao: None,
+    }).unwrap();
+    CONFIG.update(deps.storage, |mut config| -> StdResult<Config> {
+        config.delegation = Some(DelegationConfig {
+            validators: vec![ValidatorWeight { address: "validator1".to_string(), weight: Uint64::one() }],
+            unbonding_time: 60,
+            rewards_contract: rewards_contract.to_string(),
+        });
+        Ok(config)
+    }).unwrap();
+}
+
+// the chain withdraws the pending rewards of a validator whose delegation changes
+#[allow(deprecated)]
+fn delegation_changed_reply(amount: &str) -> Reply {
+    Reply {
+        id: 1,
+        payload: Binary::default(),
+        gas_used: 0,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![
+                Event::new("delegate").add_attribute("validator", "validator1"),
+                Event::new("withdraw_rewards")
+                    .add_attribute("amount", amount)
+                    .add_attribute("validator", "validator1"),
+            ],
+            data: None,
+            msg_responses: vec![],
+        }),
+    }
+}
+
+#[test]
+pub fn harvest_should_forward_rewards_withdrawn_by_delegation_changes() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let owner = deps.api.addr_make(OWNER);
+    let rewards_contract = deps.api.addr_make("rewards");
+    instantiate_delegating_staking(deps.as_mut(), &env, &owner, &rewards_contract);
+
+    crate::contract::reply(deps.as_mut(), env.clone(), delegation_changed_reply("5ustake")).unwrap();
+    crate::contract::reply(deps.as_mut(), env.clone(), delegation_changed_reply("")).unwrap();
+    crate::contract::reply(deps.as_mut(), env.clone(), delegation_changed_reply("2ustake,1uother")).unwrap();
+
+    let anyone = deps.api.addr_make("anyone");
+    let res = crate::contract::execute(deps.as_mut(), env.clone(), message_info(&anyone, &[]), ExecuteMsg::Harvest {}).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(WasmMsg::Execute {
+        contract_addr: rewards_contract.to_string(),
+        msg: to_json_binary(&RewardsExecuteMsg::DistributeRewards {}).unwrap(),
+        funds: vec![coin(1, "uother"), coin(7, "ustake")],
+    })]);
+
+    // forwarded rewards are not harvested twice
+    let err = crate::contract::execute(deps.as_mut(), env, message_info(&anyone, &[]), ExecuteMsg::Harvest {}).unwrap_err();
+    assert_eq!(err, ContractError::NothingToHarvest {});
+}
+
+#[test]
+pub fn sweep_surplus_should_leave_rewards_withdrawn_by_delegation_changes() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let owner = deps.api.addr_make(OWNER);
+    let rewards_contract = deps.api.addr_make("rewards");
+    instantiate_delegating_staking(deps.as_mut(), &env, &owner, &rewards_contract);
+
+    crate::contract::reply(deps.as_mut(), env.clone(), delegation_changed_reply("5ustake,3uother")).unwrap();
+    deps.querier.bank.update_balance(&env.contract.address, vec![coin(5, "ustake"), coin(3, "uother")]);
+
+    let reconcile: ReconcileResponse = from_json(crate::contract::query(deps.as_ref(), env.clone(), QueryMsg::Reconcile {}).unwrap()).unwrap();
+    assert_eq!(reconcile, ReconcileResponse {
+        denom: "ustake".to_string(),
+        ledger: Uint128::zero(),
+        balance: Uint128::new(5),
+        delegated: Uint128::zero(),
+        surplus: Uint128::zero(),
+        deficit: Uint128::zero(),
+        other_balances: vec![],
+        unforwarded_rewards: vec![coin(3, "uother"), coin(5, "ustake")],
+    });
+
+    let sweep = ExecuteMsg::SweepSurplus { recipient: owner.to_string() };
+    let err = crate::contract::execute(deps.as_mut(), env.clone(), message_info(&owner, &[]), sweep.clone()).unwrap_err();
+    assert_eq!(err, ContractError::NothingToSweep {});
+
+    // only the accidental transfers are swept
+    deps.querier.bank.update_balance(&env.contract.address, vec![coin(7, "ustake"), coin(4, "uother")]);
+    let res = crate::contract::execute(deps.as_mut(), env, message_info(&owner, &[]), sweep).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(BankMsg::Send {
+        to_address: owner.to_string(),
+        amount: vec![coin(1, "uother"), coin(2, "ustake")],
+    })]);
+}
+
+#[test]
+pub fn delegation_should_batch_undelegations() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let staker = app.api().addr_make("staker");
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router.staking.setup(storage, StakingInfo {
+            bonded_denom: "ustake".to_string(),
+            unbonding_time: 60,
+            apr: Decimal::percent(10),
+        }).unwrap();
+        router.staking.add_validator(api, storage, &block, Validator::create(
+            "validator1".to_string(),
+            Decimal::zero(),
+            Decimal::one(),
+            Decimal::one(),
+        )).unwrap();
+    });
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(app, None, &native_token, &None);
+    let rewards_code_id = app.store_code(rewards_receiver_contract());
+    let rewards_contract = app.instantiate_contract(rewards_code_id, owner.clone(), &Empty {}, &[], "rewards", None).unwrap();
+    app.execute_contract(owner.clone(), staking_contract.clone(), &ExecuteMsg::UpdateSettings {
+        rebond_order: None,
+        lock_tiers: None,
+        dao: None,
+        delegation: Some(DelegationConfig {
+            validators: vec![ValidatorWeight {
+                address: "validator1".to_string(),
+                weight: Uint64::one(),
+            }],
+            unbonding_time: 60,
+            rewards_contract: rewards_contract.to_string(),
+        }),
+    }, &[]).unwrap();
+
+    mint_native(app, staker.to_string(), "ustake".to_string(), 1_000u128);
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(1_000, "ustake")
+    ]).unwrap();
+
+    let delegated = |app: &App| -> Uint128 {
+        app.wrap().query_delegation(&staking_contract, "validator1").unwrap()
+            .map(|delegation| delegation.amount.amount)
+            .unwrap_or_default()
+    };
+    let unstake = |app: &mut App, amount: u128| {
+        app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+            amount: Uint128::new(amount),
+            owner: None,
+        }, &[]).unwrap();
+    };
+
+    // the first unstake is undelegated at once, the next ones wait for the interval
+    unstake(app, 100);
+    assert_eq!(delegated(app), Uint128::new(900));
+    unstake(app, 100);
+    next_block(app);
+    unstake(app, 100);
+    assert_eq!(delegated(app), Uint128::new(900));
+
+    let err = app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::ProcessUndelegations {}, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToUndelegate {});
+
+    // one undelegation per interval of a sixth of the unbonding time
+    next_block(app);
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::ProcessUndelegations {}, &[]).unwrap();
+    assert_eq!(delegated(app), Uint128::new(700));
+
+    for _ in 0..4 {
+        unstake(app, 10);
+        next_block(app);
+    }
+    assert_eq!(delegated(app), Uint128::new(670));
+
+    // claims wait for the unbonding of their batch
+    let claims: ClaimsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Claims {
+        address: staker.to_string(),
+    }).unwrap();
+    let start = app.block_info().time.minus_seconds(30);
+    assert_eq!(
+        claims.claims.iter().map(|claim| (claim.amount.u128(), claim.release_at)).collect::<Vec<_>>(),
+        vec![
+            (100, Expiration::AtTime(start.plus_seconds(60))),
+            (100, Expiration::AtTime(start.plus_seconds(70))),
+            (100, Expiration::AtTime(start.plus_seconds(70))),
+            (10, Expiration::AtTime(start.plus_seconds(80))),
+            (10, Expiration::AtTime(start.plus_seconds(80))),
+            (10, Expiration::AtTime(start.plus_seconds(80))),
+            (10, Expiration::AtTime(start.plus_seconds(90))),
+        ],
+    );
+
+    app.update_block(|block| block.time = start.plus_seconds(70));
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Claim { owner: None }, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&staker, "ustake").unwrap().amount, Uint128::new(300));
+}
+
+#[test]
+pub fn sweep_surplus_should_never_touch_staked_funds() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let staker = app.api().addr_make("staker");
+    let treasury = app.api().addr_make("treasury");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(app, None, &native_token, &None);
+
+    mint_native(app, staker.to_string(), "ustake".to_string(), 1_000u128);
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(1_000, "ustake")
+    ]).unwrap();
+
+    // accidental transfers
+    mint_native(app, staking_contract.to_string(), "ustake".to_string(), 50u128);
+    mint_native(app, staking_contract.to_string(), "uother".to_string(), 20u128);
+
+    let reconcile: ReconcileResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Reconcile {}).unwrap();
+    assert_eq!(reconcile, ReconcileResponse {
+        denom: "ustake".to_string(),
+        ledger: Uint128::new(1_000),
+        balance: Uint128::new(1_050),
+        delegated: Uint128::zero(),
+        surplus: Uint128::new(50),
+        deficit: Uint128::zero(),
+        other_balances: vec![coin(20, "uother")],
+        unforwarded_rewards: vec![],
+    });
+
+    let err = app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::SweepSurplus {
+        recipient: staker.to_string(),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Ownership(OwnershipError::NotOwner));
+
+    app.execute_contract(owner.clone(), staking_contract.clone(), &ExecuteMsg::SweepSurplus {
+        recipient: treasury.to_string(),
+    }, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&treasury, "ustake").unwrap().amount, Uint128::new(50));
+    assert_eq!(app.wrap().query_balance(&treasury, "uother").unwrap().amount, Uint128::new(20));
+
+    let err = app.execute_contract(owner, staking_contract.clone(), &ExecuteMsg::SweepSurplus {
+        recipient: treasury.to_string(),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NothingToSweep {});
+
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Unstake {
+        amount: Uint128::new(1_000),
+        owner: None,
+    }, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&staker, "ustake").unwrap().amount, Uint128::new(1_000));
+
+    let reconcile: ReconcileResponse = app.wrap().query_wasm_smart(staking_contract, &QueryMsg::Reconcile {}).unwrap();
+    assert_eq!((reconcile.ledger, reconcile.balance), (Uint128::zero(), Uint128::zero()));
+}
+
+#[test]
+pub fn migrate_should_upgrade_v0_1_state() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let staker1 = app.api().addr_make("staker1");
+    let staker2 = app.api().addr_make("staker2");
+    let staking_code_id = app.store_code(native_staking_contract());
+
+    let staking_contract = instantiate_legacy_staking(app, "crates.io:symphony-native-staking", "0.1.0");
+    mint_native(app, staking_contract.to_string(), "ustake".to_string(), 1_300u128);
+    next_block(app);
+
+    app.migrate_contract(owner.clone(), staking_contract.clone(), &MigrateMsg {}, staking_code_id).unwrap();
+
+    let config: Config = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Config {}).unwrap();
+    assert_eq!(config.unstaking_duration, Some(Duration::Time(100)));
+    assert_eq!(config.rebond_order, RebondOrder::NewestFirst);
+    assert_eq!(config.delegation, None);
+
+    let info: InfoResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Info {}).unwrap();
+    assert_eq!(info.info.version, env!("CARGO_PKG_VERSION"));
+
+    // staked and unbonding tokens are both accounted for in the ledger
+    let reconcile: ReconcileResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Reconcile {}).unwrap();
+    assert_eq!((reconcile.ledger, reconcile.surplus, reconcile.deficit), (Uint128::new(1_300), Uint128::zero(), Uint128::zero()));
+
+    let staked_balance: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
+        address: staker1.to_string(),
+        height: None,
+    }).unwrap();
+    assert_eq!(staked_balance.balance, Uint128::new(700));
+
+    app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Rebond {
+        amount: Uint128::new(100),
+        owner: None,
+    }, &[]).unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+    app.execute_contract(staker2.clone(), staking_contract.clone(), &ExecuteMsg::Claim { owner: None }, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&staker2, "ustake").unwrap().amount, Uint128::new(200));
+
+    // migrating again to the same version is a no-op
+    app.migrate_contract(owner, staking_contract, &MigrateMsg {}, staking_code_id).unwrap();
+}
+
+#[test]
+pub fn migrate_should_refuse_downgrades_and_other_contracts() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let staking_code_id = app.store_code(native_staking_contract());
+
+    let newer_contract = instantiate_legacy_staking(app, "crates.io:symphony-native-staking", "9.0.0");
+    let err = app.migrate_contract(owner.clone(), newer_contract, &MigrateMsg {}, staking_code_id).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Migration(MigrationError::Downgrade {
+        stored: "9.0.0".to_string(),
+        new: env!("CARGO_PKG_VERSION").to_string(),
+    }));
+
+    let other_contract = instantiate_legacy_staking(app, "crates.io:cw20-base", "0.1.0");
+    let err = app.migrate_contract(owner, other_contract, &MigrateMsg {}, staking_code_id).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Migration(MigrationError::WrongContract {
+        expected: "crates.io:symphony-native-staking".to_string(),
+        found: "crates.io:cw20-base".to_string(),
+    }));
+}
+
+#[test]
+pub fn operators_should_only_act_with_unexpired_permissions() {
+    let app = &mut mock_app();
+    let staker = app.api().addr_make("staker");
+    let operator = app.api().addr_make("operator");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(app, None, &native_token, &Some(Duration::Time(5)));
+
+    mint_native(app, staker.to_string(), "ustake".to_string(), 1_000u128);
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(1_000, "ustake")
+    ]).unwrap();
+
+    let unstake = ExecuteMsg::Unstake {
+        amount: Uint128::new(300),
+        owner: Some(staker.to_string()),
+    };
+    let err = app.execute_contract(operator.clone(), staking_contract.clone(), &unstake, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NotApproved { permission: Permission::Unstake });
+
+    let err = app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Approve {
+        operator: operator.to_string(),
+        expires: None,
+        permissions: vec![],
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NoPermissions {});
+
+    let expires = Expiration::AtTime(app.block_info().time.plus_seconds(100));
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Approve {
+        operator: operator.to_string(),
+        expires: Some(expires),
+        permissions: vec![Permission::Unstake, Permission::Claim],
+    }, &[]).unwrap();
+
+    let approvals: ApprovalsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Approvals {
+        owner: staker.to_string(),
+        start_after: None,
+        limit: None,
+    }).unwrap();
+    assert_eq!(approvals.approvals, vec![ApprovalResponse {
+        operator: operator.to_string(),
+        expires,
+        permissions: vec![Permission::Unstake, Permission::Claim],
+    }]);
+
+    app.execute_contract(operator.clone(), staking_contract.clone(), &unstake, &[]).unwrap();
+    let err = app.execute_contract(operator.clone(), staking_contract.clone(), &ExecuteMsg::Rebond {
+        amount: Uint128::new(100),
+        owner: Some(staker.to_string()),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NotApproved { permission: Permission::Rebond });
+
+    // the claimed tokens go to the owner, not the operator
+    next_block(app);
+    app.execute_contract(operator.clone(), staking_contract.clone(), &ExecuteMsg::Claim {
+        owner: Some(staker.to_string()),
+    }, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&staker, "ustake").unwrap().amount, Uint128::new(300));
+    assert_eq!(app.wrap().query_balance(&operator, "ustake").unwrap().amount, Uint128::zero());
+
+    for _ in 0..20 {
+        next_block(app);
+    }
+    let err = app.execute_contract(operator.clone(), staking_contract.clone(), &unstake, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NotApproved { permission: Permission::Unstake });
+    let approvals: ApprovalsResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::Approvals {
+        owner: staker.to_string(),
+        start_after: None,
+        limit: None,
+    }).unwrap();
+    assert!(approvals.approvals.is_empty());
+
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Approve {
+        operator: operator.to_string(),
+        expires: None,
+        permissions: vec![Permission::Unstake],
+    }, &[]).unwrap();
+    app.execute_contract(staker.clone(), staking_contract.clone(), &ExecuteMsg::Revoke {
+        operator: operator.to_string(),
+    }, &[]).unwrap();
+    let err = app.execute_contract(operator, staking_contract, &unstake, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::NotApproved { permission: Permission::Unstake });
+}
+
+#[test]
+pub fn stake_for_should_credit_every_recipient() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let distributor = app.api().addr_make("distributor");
+    let alice = app.api().addr_make("alice");
+    let bob = app.api().addr_make("bob");
+    let native_token = DenomUnit {
+        denom: "ustake".to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let staking_contract = instantiate_staking(app, None, &native_token, &None);
+    let receiver = instantiate_hook_receiver(app);
+    app.execute_contract(owner, staking_contract.clone(), &ExecuteMsg::AddHook {
+        addr: receiver.to_string(),
+    }, &[]).unwrap();
+
+    mint_native(app, distributor.to_string(), "ustake".to_string(), 1_000u128);
+    let recipients = vec![(alice.to_string(), Uint128::new(300)), (bob.to_string(), Uint128::new(200))];
+
+    let err = app.execute_contract(distributor.clone(), staking_contract.clone(), &ExecuteMsg::StakeFor {
+        recipients: recipients.clone(),
+    }, &[coin(600, "ustake")]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::StakeForFundsMismatch {
+        expected: Uint128::new(500),
+        received: Uint128::new(600),
+    });
+
+    let res = app.execute_contract(distributor.clone(), staking_contract.clone(), &ExecuteMsg::StakeFor {
+        recipients,
+    }, &[coin(500, "ustake")]).unwrap();
+    let hook_calls = res.events.iter()
+        .filter(|event| event.ty == "execute" && event.attributes.iter().any(|attr| attr.value == receiver.as_str()))
+        .count();
+    assert_eq!(hook_calls, 2);
+
+    let hook: StakeChangedHookMsg = app.wrap().query_wasm_smart(receiver, &Empty {}).unwrap();
+    assert_eq!(hook, StakeChangedHookMsg {
+        addr: bob.to_string(),
+        old_balance: Uint128::zero(),
+        new_balance: Uint128::new(200),
+        denom: "ustake".to_string(),
+    });
+
+    next_block(app);
+    for (staker, amount) in [(&alice, 300u128), (&bob, 200), (&distributor, 0)] {
+        let staked: StakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(staking_contract.clone(), &QueryMsg::StakedBalanceAtHeight {
+            address: staker.to_string(),
+            height: None,
+        }).unwrap();
+        assert_eq!(staked.balance, Uint128::new(amount));
+    }
+    let total: TotalStakedAtHeightResponse = app.wrap().query_wasm_smart(staking_contract, &QueryMsg::TotalStakedAtHeight {
+        height: None,
+    }).unwrap();
+    assert_eq!(total.total, Uint128::new(500));
+}
@@ -3,18 +3,28 @@ use cosmwasm_std::entry_point;
 use std::collections::HashMap;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg};
-use crate::state::STAKING_CONTRACTS;
-use cosmwasm_std::{to_json_binary, Binary, DenomUnit, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, WasmMsg};
+use crate::migrations::MIGRATIONS;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, StakingContractChange};
+use crate::state::{RETIRED_STAKING_CONTRACTS, REWARDS_CONTRACT, STAKING_CONTRACTS};
+use cosmwasm_schema::serde::Deserialize;
+use cosmwasm_std::{from_json, to_json_binary, Addr, Binary, DenomUnit, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg};
 use cw2::set_contract_version;
-use cw_ownable::get_ownership;
+use symphony_utils::migrate::migrate_contract;
+use cw_controllers::HooksResponse;
+use cw_ownable::{get_ownership, Ownership};
+use cw_storage_plus::Bound;
 use cw_utils::{parse_instantiate_response_data, Duration};
-use symphony_interfaces::orchestrator::{AllTokensStakedBalanceAtHeightResponse, ListStakersByDenomResponse, QueryMsg, RegisteredContract, StakingContractByDenomResponse};
+use symphony_interfaces::orchestrator::{AllTokensStakedBalanceAtHeightResponse, ContractStatus, IsRewardedResponse, ListStakersByDenomResponse, ListStakingContractsResponse, QueryMsg, RegisteredContract, RetiredStakingContractsResponse, RewardsContractResponse, RewardsQueryMsg, StakingContractByDenomResponse, StakingContractResponse, StakingToken, TokenKind};
 
 pub(crate) const CONTRACT_NAME: &str = "crates.io:symphony-staking-orchestrator";
 pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const INSTANTIATE_STAKING_REPLY_ID: u64 = 1;
+const INSTANTIATE_CW20_STAKING_REPLY_ID: u64 = 2;
+const MIGRATE_STAKING_REPLY_ID: u64 = 3;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -42,8 +52,27 @@ pub fn execute(deps: DepsMut,
                msg: ExecuteMsg) -> Result<Response<Empty>, ContractError> {
     match msg {
         ExecuteMsg::UpdateOwnership(action) => execute_update_owner(deps, info, env, action),
-        ExecuteMsg::CreateStakingContract { code_id, denom_unit, unbonding_period, owner } => {
-            execute_create_staking_contract(deps, env, info, code_id, denom_unit, unbonding_period, owner)
+        ExecuteMsg::CreateStakingContract { code_id, token, unbonding_period, owner } => {
+            execute_create_staking_contract(deps, env, info, code_id, token, unbonding_period, owner)
+        }
+        ExecuteMsg::SetRewardsContract { address } => execute_set_rewards_contract(deps, info, address),
+        ExecuteMsg::RegisterStakingContract { address } => execute_register_staking_contract(deps, env, info, address),
+        ExecuteMsg::ReplaceStakingContract { address } => execute_replace_staking_contract(deps, env, info, address),
+        ExecuteMsg::DeregisterStakingContract { denom } => execute_deregister_staking_contract(deps, info, denom),
+        ExecuteMsg::Stake {} => execute_stake(deps, info),
+        ExecuteMsg::Unstake { denom, amount } => execute_unstake(deps, info, denom, amount),
+        ExecuteMsg::Claim { denoms } => execute_claim(deps, info, denoms),
+        ExecuteMsg::UpdateStakingContractConfig { denom, unbonding_period } => {
+            execute_update_staking_contracts(deps, info, vec![denom], StakingContractChange::UpdateConfig { unbonding_period })
+        }
+        ExecuteMsg::TransferStakingContractOwnership { denom, new_owner } => {
+            execute_update_staking_contracts(deps, info, vec![denom], StakingContractChange::TransferOwnership { new_owner })
+        }
+        ExecuteMsg::UpdateStakingContracts { denoms, change } => {
+            execute_update_staking_contracts(deps, info, denoms, change)
+        }
+        ExecuteMsg::MigrateStakingContracts { new_code_id, msg, denoms } => {
+            execute_migrate_staking_contracts(deps, info, new_code_id, msg, denoms)
         }
     }
 }
@@ -63,7 +92,7 @@ pub fn execute_create_staking_contract(
     env: Env,
     info: MessageInfo,
     code_id: u64,
-    denom_unit: DenomUnit,
+    token: StakingToken,
     unbonding_period: Option<Duration>,
     owner: Option<String>,
 ) -> Result<Response<Empty>, ContractError> {
@@ -73,32 +102,413 @@ pub fn execute_create_staking_contract(
         owner.as_deref().unwrap_or(env.contract.address.as_str())
     )?;
 
-    let msg = symphony_interfaces::staking::InstantiateMsg {
-        owner: Some(selected_owner.to_string()),
-        denom_unit: denom_unit.clone(),
-        unbonding_period,
+    // new staking contracts notify the rewards contract of stake changes
+    let hooks = REWARDS_CONTRACT.may_load(deps.storage)?
+        .map(|rewards_contract| vec![rewards_contract.to_string()]);
+
+    let (denom, msg, reply_id) = match token {
+        StakingToken::Native(denom_unit) => {
+            let msg = symphony_interfaces::staking::InstantiateMsg {
+                owner: Some(selected_owner.to_string()),
+                denom_unit: denom_unit.clone(),
+                unbonding_period,
+                hooks,
+                dao: None,
+            };
+            (denom_unit.denom, to_json_binary(&msg)?, INSTANTIATE_STAKING_REPLY_ID)
+        }
+        StakingToken::Cw20 { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let msg = symphony_interfaces::cw20_staking::InstantiateMsg {
+                owner: Some(selected_owner.to_string()),
+                token_address: address.to_string(),
+                unbonding_period,
+                hooks,
+                dao: None,
+            };
+            (address.to_string(), to_json_binary(&msg)?, INSTANTIATE_CW20_STAKING_REPLY_ID)
+        }
     };
 
+    if STAKING_CONTRACTS.has(deps.storage, &denom) {
+        return Err(ContractError::DenomAlreadyRegistered { denom });
+    }
+
     let init_msg = WasmMsg::Instantiate {
         admin: Some(selected_owner.to_string()),
         code_id,
-        msg: to_json_binary(&msg.clone())?,
+        msg,
         funds: info.funds,
-        label: format!("{} staking contract", denom_unit.denom),
+        label: format!("{} staking contract", denom),
     };
 
-    let msg = SubMsg::reply_on_success(init_msg, INSTANTIATE_STAKING_REPLY_ID);
+    let msg = SubMsg::reply_on_success(init_msg, reply_id);
 
     Ok(
         Response::new()
             .add_attribute("action", "create_staking_contract")
-            .add_attribute("denom", denom_unit.denom)
+            .add_attribute("denom", denom)
             .add_attribute("owner", selected_owner)
             .add_attribute("code_id", format!("{}", code_id))
             .add_submessage(msg)
     )
 }
 
+pub fn execute_set_rewards_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response<Empty>, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let rewards_contract = deps.api.addr_validate(&address)?;
+    REWARDS_CONTRACT.save(deps.storage, &rewards_contract)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "set_rewards_contract")
+            .add_attribute("address", rewards_contract)
+    )
+}
+
+/// Adopted contracts must notify the rewards contract, which is added to the hooks of the
+/// ones the orchestrator owns.
+pub fn execute_register_staking_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response<Empty>, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    let contract = registration(deps.as_ref(), &env, address.to_string())?;
+    let hook_msg = rewards_hook_msg(deps.as_ref(), &env, &contract)?;
+    register(deps.storage, &contract)?;
+
+    Ok(
+        Response::new()
+            .add_messages(hook_msg)
+            .add_attribute("action", "register_staking_contract")
+            .add_attribute("denom", contract.token.denom)
+            .add_attribute("address", contract.address)
+    )
+}
+
+/// The replaced contract keeps notifying the rewards contract, so its stakers still settle
+/// the rewards they accrued in it.
+pub fn execute_replace_staking_contract(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response<Empty>, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    let contract = registration(deps.as_ref(), &env, address.to_string())?;
+    let hook_msg = rewards_hook_msg(deps.as_ref(), &env, &contract)?;
+    let denom = contract.token.denom.clone();
+
+    let replaced = STAKING_CONTRACTS.may_load(deps.storage, &denom)?
+        .ok_or_else(|| ContractError::DenomNotRegistered { denom: denom.clone() })?;
+    if replaced.address == contract.address {
+        return Err(ContractError::DenomAlreadyRegistered { denom });
+    }
+
+    retire(deps.storage, replaced.clone(), ContractStatus::Deprecated)?;
+    STAKING_CONTRACTS.save(deps.storage, &denom, &contract)?;
+
+    Ok(
+        Response::new()
+            .add_messages(hook_msg)
+            .add_attribute("action", "replace_staking_contract")
+            .add_attribute("denom", denom)
+            .add_attribute("address", contract.address)
+            .add_attribute("replaced", replaced.address)
+    )
+}
+
+/// Refused while the rewards contract still distributes to the denom.
+pub fn execute_deregister_staking_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response<Empty>, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let contract = STAKING_CONTRACTS.may_load(deps.storage, &denom)?
+        .ok_or_else(|| ContractError::DenomNotRegistered { denom: denom.clone() })?;
+    if let Some(rewards_contract) = REWARDS_CONTRACT.may_load(deps.storage)? {
+        let response: IsRewardedResponse = deps.querier.query_wasm_smart(
+            rewards_contract,
+            &RewardsQueryMsg::IsRewarded { denom: denom.clone() },
+        )?;
+        if response.rewarded {
+            return Err(ContractError::DenomRewarded { denom });
+        }
+    }
+
+    STAKING_CONTRACTS.remove(deps.storage, &denom);
+    retire(deps.storage, contract.clone(), ContractStatus::Closed)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "deregister_staking_contract")
+            .add_attribute("denom", denom)
+            .add_attribute("address", contract.address)
+    )
+}
+
+pub fn execute_stake(deps: DepsMut, info: MessageInfo) -> Result<Response<Empty>, ContractError> {
+    if info.funds.is_empty() {
+        return Err(ContractError::NoFunds {});
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "stake")
+        .add_attribute("from", info.sender.as_str());
+    for coin in info.funds {
+        let contract = routed_contract(deps.as_ref(), &coin.denom)?;
+        response = response
+            .add_message(WasmMsg::Execute {
+                contract_addr: contract.address,
+                msg: to_json_binary(&symphony_interfaces::staking::ExecuteMsg::StakeFor {
+                    recipients: vec![(info.sender.to_string(), coin.amount)],
+                })?,
+                funds: vec![coin.clone()],
+            })
+            .add_attribute("denom", coin.denom)
+            .add_attribute("amount", coin.amount);
+    }
+
+    Ok(response)
+}
+
+pub fn execute_unstake(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+) -> Result<Response<Empty>, ContractError> {
+    let contract = routed_contract(deps.as_ref(), &denom)?;
+
+    Ok(
+        Response::new()
+            .add_message(WasmMsg::Execute {
+                contract_addr: contract.address,
+                msg: to_json_binary(&symphony_interfaces::staking::ExecuteMsg::Unstake {
+                    amount,
+                    owner: Some(info.sender.to_string()),
+                })?,
+                funds: vec![],
+            })
+            .add_attribute("action", "unstake")
+            .add_attribute("from", info.sender)
+            .add_attribute("denom", denom)
+            .add_attribute("amount", amount)
+    )
+}
+
+pub fn execute_claim(
+    deps: DepsMut,
+    info: MessageInfo,
+    denoms: Vec<String>,
+) -> Result<Response<Empty>, ContractError> {
+    if denoms.is_empty() {
+        return Err(ContractError::NoDenoms {});
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "claim")
+        .add_attribute("from", info.sender.as_str());
+    for denom in denoms {
+        let contract = routed_contract(deps.as_ref(), &denom)?;
+        response = response
+            .add_message(WasmMsg::Execute {
+                contract_addr: contract.address,
+                msg: to_json_binary(&symphony_interfaces::staking::ExecuteMsg::Claim {
+                    owner: Some(info.sender.to_string()),
+                })?,
+                funds: vec![],
+            })
+            .add_attribute("denom", denom);
+    }
+
+    Ok(response)
+}
+
+/// Only works on the staking contracts the orchestrator owns.
+pub fn execute_update_staking_contracts(
+    deps: DepsMut,
+    info: MessageInfo,
+    denoms: Vec<String>,
+    change: StakingContractChange,
+) -> Result<Response<Empty>, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    if denoms.is_empty() {
+        return Err(ContractError::NoDenoms {});
+    }
+
+    let action = match &change {
+        StakingContractChange::UpdateConfig { .. } => "update_staking_contract_config",
+        StakingContractChange::TransferOwnership { new_owner } => {
+            deps.api.addr_validate(new_owner)?;
+            "transfer_staking_contract_ownership"
+        }
+    };
+
+    let mut response = Response::new().add_attribute("action", action);
+    for denom in denoms {
+        let contract = STAKING_CONTRACTS.may_load(deps.storage, &denom)?
+            .ok_or_else(|| ContractError::DenomNotRegistered { denom: denom.clone() })?;
+        response = response
+            .add_message(staking_contract_change_msg(&contract, &change)?)
+            .add_attribute("denom", denom);
+    }
+
+    Ok(response)
+}
+
+/// Only works on the staking contracts the orchestrator is admin of.
+pub fn execute_migrate_staking_contracts(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_code_id: u64,
+    msg: Binary,
+    denoms: Option<Vec<String>>,
+) -> Result<Response<Empty>, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let contracts = match denoms {
+        None => STAKING_CONTRACTS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, contract)| contract))
+            .collect::<StdResult<Vec<_>>>()?,
+        Some(denoms) => denoms.into_iter()
+            .map(|denom| STAKING_CONTRACTS.may_load(deps.storage, &denom)?
+                .ok_or(ContractError::DenomNotRegistered { denom }))
+            .collect::<Result<Vec<_>, ContractError>>()?,
+    };
+    if contracts.is_empty() {
+        return Err(ContractError::NoDenoms {});
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "migrate_staking_contracts")
+        .add_attribute("code_id", new_code_id.to_string());
+    for contract in contracts {
+        let migrate_msg = WasmMsg::Migrate {
+            contract_addr: contract.address,
+            new_code_id,
+            msg: msg.clone(),
+        };
+        response = response.add_submessage(
+            SubMsg::reply_always(migrate_msg, MIGRATE_STAKING_REPLY_ID)
+                .with_payload(to_json_binary(&contract.token.denom)?)
+        );
+    }
+
+    Ok(response)
+}
+
+fn staking_contract_change_msg(contract: &RegisteredContract, change: &StakingContractChange) -> StdResult<WasmMsg> {
+    let msg = match (change, &contract.kind) {
+        (StakingContractChange::UpdateConfig { unbonding_period }, TokenKind::Native) => {
+            to_json_binary(&symphony_interfaces::staking::ExecuteMsg::UpdateConfig {
+                unbonding_period: *unbonding_period,
+            })?
+        }
+        (StakingContractChange::UpdateConfig { unbonding_period }, TokenKind::Cw20) => {
+            to_json_binary(&symphony_interfaces::cw20_staking::ExecuteMsg::UpdateConfig {
+                unbonding_period: *unbonding_period,
+            })?
+        }
+        // both staking contracts share the cw-ownable message
+        (StakingContractChange::TransferOwnership { new_owner }, _) => {
+            to_json_binary(&symphony_interfaces::staking::ExecuteMsg::UpdateOwnership(cw_ownable::Action::TransferOwnership {
+                new_owner: new_owner.clone(),
+                expiry: None,
+            }))?
+        }
+    };
+
+    Ok(WasmMsg::Execute {
+        contract_addr: contract.address.clone(),
+        msg,
+        funds: vec![],
+    })
+}
+
+/// Native staking contract registered for `denom`. CW20 staking contracts take their stake
+/// through a CW20 `Send`, which the orchestrator cannot make for the sender's tokens.
+fn routed_contract(deps: Deps, denom: &str) -> Result<RegisteredContract, ContractError> {
+    let contract = STAKING_CONTRACTS.may_load(deps.storage, &denom.to_string())?
+        .ok_or_else(|| ContractError::DenomNotRegistered { denom: denom.to_string() })?;
+    if contract.kind != TokenKind::Native {
+        return Err(ContractError::RoutingUnsupported { denom: denom.to_string() });
+    }
+    Ok(contract)
+}
+
+/// Registers `contract` for its denom, refusing to overwrite another registration.
+fn register(storage: &mut dyn Storage, contract: &RegisteredContract) -> Result<(), ContractError> {
+    if STAKING_CONTRACTS.has(storage, &contract.token.denom) {
+        return Err(ContractError::DenomAlreadyRegistered { denom: contract.token.denom.clone() });
+    }
+    STAKING_CONTRACTS.save(storage, &contract.token.denom, contract)?;
+    Ok(())
+}
+
+fn retire(storage: &mut dyn Storage, mut contract: RegisteredContract, status: ContractStatus) -> StdResult<()> {
+    contract.status = status;
+    RETIRED_STAKING_CONTRACTS.save(storage, (&contract.token.denom, &contract.address), &contract)
+}
+
+/// Adds the rewards contract to the hooks of an adopted contract the orchestrator owns.
+/// Contracts owned by others must list it already, as the rewards contract only accrues
+/// for stakers it is notified about.
+fn rewards_hook_msg(deps: Deps, env: &Env, contract: &RegisteredContract) -> Result<Option<WasmMsg>, ContractError> {
+    let Some(rewards_contract) = REWARDS_CONTRACT.may_load(deps.storage)? else {
+        return Ok(None);
+    };
+
+    let hooks: HooksResponse = deps.querier.query_wasm_smart(
+        &contract.address,
+        &symphony_interfaces::staking::QueryMsg::Hooks {},
+    )?;
+    if hooks.hooks.contains(&rewards_contract.to_string()) {
+        return Ok(None);
+    }
+
+    let ownership: Ownership<Addr> = deps.querier.query_wasm_smart(
+        &contract.address,
+        &symphony_interfaces::staking::QueryMsg::Ownership {},
+    )?;
+    if ownership.owner.as_ref() != Some(&env.contract.address) {
+        return Err(ContractError::MissingRewardsHook { address: contract.address.clone() });
+    }
+
+    // both staking contracts share the hook messages
+    Ok(Some(WasmMsg::Execute {
+        contract_addr: contract.address.clone(),
+        msg: to_json_binary(&symphony_interfaces::staking::ExecuteMsg::AddHook {
+            addr: rewards_contract.to_string(),
+        })?,
+        funds: vec![],
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from_version = migrate_contract(deps.storage, &env, CONTRACT_NAME, CONTRACT_VERSION, MIGRATIONS)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -109,6 +519,11 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&query_all_tokens_staked_balance_at_height(deps, address, height)?),
         QueryMsg::ListStakersByDenom { denom, start_after, limit } =>
             to_json_binary(&query_list_stakers_by_denom(deps, denom, start_after, limit)?),
+        QueryMsg::RewardsContract {} => to_json_binary(&query_rewards_contract(deps)?),
+        QueryMsg::ListStakingContracts { start_after, limit } =>
+            to_json_binary(&query_list_staking_contracts(deps, start_after, limit)?),
+        QueryMsg::RetiredStakingContracts { denom } =>
+            to_json_binary(&query_retired_staking_contracts(deps, denom)?),
     }
 }
 
@@ -152,15 +567,85 @@ pub fn query_all_tokens_staked_balance_at_height(
     })
 }
 
-fn query_staking_contract_config(deps: Deps, address: String) -> StdResult<RegisteredContract> {
-    let result: symphony_interfaces::staking::ConfigResponse = deps.querier.query_wasm_smart(
+pub fn query_rewards_contract(deps: Deps) -> StdResult<RewardsContractResponse> {
+    let address = REWARDS_CONTRACT.may_load(deps.storage)?;
+
+    Ok(RewardsContractResponse {
+        address: address.map(|addr| addr.to_string()),
+    })
+}
+
+pub fn query_list_staking_contracts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListStakingContractsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let contracts = STAKING_CONTRACTS
+        .range(deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (denom, registered_contract) = item?;
+            let config = deps.querier.query_wasm_smart(
+                &registered_contract.address,
+                &symphony_interfaces::staking::QueryMsg::Config {},
+            )?;
+            let total_staked = deps.querier.query_wasm_smart(
+                &registered_contract.address,
+                &symphony_interfaces::staking::QueryMsg::TotalStakedAtHeight { height: None },
+            )?;
+
+            Ok(StakingContractResponse {
+                denom,
+                registered_contract,
+                config,
+                total_staked,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListStakingContractsResponse { contracts })
+}
+
+pub fn query_retired_staking_contracts(deps: Deps, denom: String) -> StdResult<RetiredStakingContractsResponse> {
+    let contracts = RETIRED_STAKING_CONTRACTS
+        .prefix(&denom)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, contract)| contract))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RetiredStakingContractsResponse { denom, contracts })
+}
+
+/// Part of a staking contract config needed to register it. Decoded on its own, so the
+/// contracts of older releases without the newer config fields can be registered too.
+#[derive(Deserialize)]
+#[serde(crate = "cosmwasm_schema::serde")]
+struct StakingConfig {
+    staking_token: DenomUnit,
+    #[serde(default)]
+    token_kind: TokenKind,
+}
+
+/// Active registration of the staking contract at `address`, for the token and token kind
+/// declared in its config.
+fn registration(deps: Deps, env: &Env, address: String) -> StdResult<RegisteredContract> {
+    let config: StakingConfig = deps.querier.query_wasm_smart(
         address.clone(),
         &symphony_interfaces::staking::QueryMsg::Config {},
     )?;
+    let code_id = deps.querier.query_wasm_contract_info(&address)?.code_id;
+    let version = cw2::query_contract_info(&deps.querier, &address).ok().map(|info| info.version);
 
     let contract = RegisteredContract {
         address,
-        token: result.staking_token,
+        token: config.staking_token,
+        kind: config.token_kind,
+        registered_at: env.block.time,
+        status: ContractStatus::Active,
+        code_id: Some(code_id),
+        version,
     };
 
     Ok(contract)
@@ -195,30 +680,26 @@ fn query_staking_contract_for_list_stakers(deps: Deps, contract_addr: &str, star
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
     match msg.id {
-        INSTANTIATE_STAKING_REPLY_ID => {
+        INSTANTIATE_STAKING_REPLY_ID | INSTANTIATE_CW20_STAKING_REPLY_ID => {
             handle_instantiate_staking_reply(deps, env, msg)
         },
-        _ => Err(StdError::generic_err("Invalid reply ID")),
+        MIGRATE_STAKING_REPLY_ID => handle_migrate_staking_reply(deps, msg),
+        id => Err(ContractError::UnknownReplyId { id }),
     }
 }
 
-fn decode_and_handle_binary_data(deps: DepsMut, bin: &Binary) -> StdResult<Response> {
-    let decoded = parse_instantiate_response_data(&bin)
+fn decode_and_handle_binary_data(deps: DepsMut, env: &Env, bin: &Binary) -> Result<Response, ContractError> {
+    let decoded = parse_instantiate_response_data(bin)
         .map_err(|e| StdError::generic_err(format!("parsing submsg response: {}", e)))?;
 
-    let contract_config = query_staking_contract_config(
+    let contract = registration(
         deps.as_ref(),
-        decoded.contract_address.clone()
+        env,
+        decoded.contract_address.clone(),
     )?;
-
-    let contract = RegisteredContract {
-        address: decoded.contract_address.clone(),
-        token: contract_config.token.clone(),
-    };
-
-    STAKING_CONTRACTS.save(deps.storage, &contract_config.token.denom, &contract)?;
+    register(deps.storage, &contract)?;
 
     Ok(
         Response::new()
@@ -231,27 +712,57 @@ fn decode_and_handle_binary_data(deps: DepsMut, bin: &Binary) -> StdResult<Respo
 #[allow(deprecated)]
 fn handle_instantiate_staking_reply(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     msg: Reply,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     match msg.result.into_result() {
         Err(e) => {
-            Err(StdError::generic_err(format!("SubMsg failed: {}", e)))
+            Err(StdError::generic_err(format!("SubMsg failed: {}", e)).into())
         }
         Ok(sub_msg) => {
             match sub_msg.data {
                 None => {
                     match sub_msg.msg_responses.first() {
-                        None => Err(StdError::generic_err("No submsg response")),
+                        None => Err(StdError::generic_err("No submsg response").into()),
                         Some(response) => {
-                            decode_and_handle_binary_data(deps, &response.value)
+                            decode_and_handle_binary_data(deps, &env, &response.value)
                         }
                     }
                 }
                 Some(bin) => {
-                    decode_and_handle_binary_data(deps, &bin)
+                    decode_and_handle_binary_data(deps, &env, &bin)
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Records the new code ID and version of a migrated contract. Failures are reported
+/// without reverting the other migrations.
+fn handle_migrate_staking_reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    let denom: String = from_json(&msg.payload)?;
+    let response = Response::new()
+        .add_attribute("action", "migrate_staking_contract")
+        .add_attribute("denom", denom.as_str());
+
+    match msg.result.into_result() {
+        Err(error) => Ok(
+            response
+                .add_attribute("result", "failure")
+                .add_attribute("error", error)
+        ),
+        Ok(_) => {
+            let mut contract = STAKING_CONTRACTS.load(deps.storage, &denom)?;
+            contract.code_id = Some(deps.querier.query_wasm_contract_info(&contract.address)?.code_id);
+            contract.version = cw2::query_contract_info(&deps.querier, &contract.address).ok().map(|info| info.version);
+            STAKING_CONTRACTS.save(deps.storage, &denom, &contract)?;
+
+            Ok(
+                response
+                    .add_attribute("result", "success")
+                    .add_attribute("address", contract.address)
+                    .add_attribute("version", contract.version.unwrap_or_default())
+            )
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use symphony_utils::migrate::MigrationError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +7,9 @@ pub enum ContractError {
     #[error(transparent)]
     Std(#[from] StdError),
 
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+
     #[error(transparent)]
     Ownership(#[from] cw_ownable::OwnershipError),
 
@@ -17,6 +21,27 @@ pub enum ContractError {
         id: u64,
     },
 
+    #[error("A staking contract is already registered for {denom}")]
+    DenomAlreadyRegistered { denom: String },
+
+    #[error("No staking contract is registered for {denom}")]
+    DenomNotRegistered { denom: String },
+
+    #[error("Staking contract {address} does not notify the rewards contract and is not owned by the orchestrator")]
+    MissingRewardsHook { address: String },
+
+    #[error("{denom} is still part of the rewards distribution")]
+    DenomRewarded { denom: String },
+
+    #[error("Only native staking contracts can be used through the orchestrator, use the CW20 staking contract for {denom} directly")]
+    RoutingUnsupported { denom: String },
+
+    #[error("No funds to stake")]
+    NoFunds {},
+
+    #[error("No denoms to claim")]
+    NoDenoms {},
+
     #[error("Cannot instantiate staking contract")]
     SubContractInstantiationFailed {},
 }
\ No newline at end of file
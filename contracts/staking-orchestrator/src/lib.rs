@@ -2,6 +2,7 @@ pub mod state;
 pub mod msg;
 pub mod contract;
 pub mod error;
+pub mod migrations;
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
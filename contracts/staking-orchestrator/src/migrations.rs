@@ -0,0 +1,40 @@
+use crate::error::ContractError;
+use crate::state::STAKING_CONTRACTS;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{DenomUnit, Env, Order, StdResult, Storage};
+use cw_storage_plus::Map;
+use symphony_interfaces::orchestrator::{ContractStatus, RegisteredContract, TokenKind};
+use symphony_utils::migrate::Migration;
+
+/// State transformers, by the version they migrate to.
+pub const MIGRATIONS: &[Migration<ContractError>] = &[("0.2.0", migrate_to_v0_2)];
+
+#[cw_serde]
+pub struct RegisteredContractV0_1 {
+    pub address: String,
+    pub token: DenomUnit,
+}
+
+pub const STAKING_CONTRACTS_V0_1: Map<&String, RegisteredContractV0_1> = Map::new("staking_contracts");
+
+/// Only native staking contracts could be created before CW20 support. The registration
+/// time was not recorded, so the migration time stands in for it.
+fn migrate_to_v0_2(storage: &mut dyn Storage, env: &Env) -> Result<(), ContractError> {
+    let contracts = STAKING_CONTRACTS_V0_1
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (denom, contract) in contracts {
+        STAKING_CONTRACTS.save(storage, &denom, &RegisteredContract {
+            address: contract.address,
+            token: contract.token,
+            kind: TokenKind::Native,
+            registered_at: env.block.time,
+            status: ContractStatus::Active,
+            code_id: None,
+            version: None,
+        })?;
+    }
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::DenomUnit;
 use cw_ownable::cw_ownable_execute;
+use cosmwasm_std::{Binary, Uint128};
 use cw_utils::Duration;
+use symphony_interfaces::orchestrator::StakingToken;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -9,13 +10,85 @@ pub struct InstantiateMsg {
 }
 
 
+/// Owner action on a child staking contract.
+#[cw_serde]
+pub enum StakingContractChange {
+    UpdateConfig {
+        unbonding_period: Option<Duration>,
+    },
+    /// Proposes `new_owner`, who still has to accept the ownership on the child contract.
+    TransferOwnership {
+        new_owner: String,
+    },
+}
+
 #[cw_ownable_execute]
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateStakingContract {
         code_id: u64,
-        denom_unit: DenomUnit,
+        token: StakingToken,
         unbonding_period: Option<Duration>,
         owner: Option<String>,
-    }
-}
\ No newline at end of file
+    },
+    SetRewardsContract {
+        address: String,
+    },
+    /// Adopts an existing staking contract for a denom not registered yet. Once a rewards
+    /// contract is set, the adopted contract must notify it, or be owned by the orchestrator
+    /// so the hook can be added.
+    RegisterStakingContract {
+        address: String,
+    },
+    /// Registers `address` in place of the contract registered for its denom, which is
+    /// kept as deprecated and keeps notifying the rewards contract. The new contract must
+    /// notify it as when registering.
+    ReplaceStakingContract {
+        address: String,
+    },
+    /// Removes the contract registered for `denom`, which is kept as closed. Refused while
+    /// the rewards contract distributes to `denom`.
+    DeregisterStakingContract {
+        denom: String,
+    },
+    /// Stakes each sent coin for the sender in the contract registered for its denom. Only
+    /// native staking contracts are routed; CW20 tokens are staked by sending them to their
+    /// staking contract directly.
+    Stake {},
+    /// Unstakes for the sender, who must first `Approve` the orchestrator with the `Unstake`
+    /// permission in the native staking contract of `denom`, since that contract only sees
+    /// the orchestrator as sender.
+    Unstake {
+        denom: String,
+        amount: Uint128,
+    },
+    /// Claims for the sender, who must first `Approve` the orchestrator with the `Claim`
+    /// permission in the native staking contract of every denom.
+    Claim {
+        denoms: Vec<String>,
+    },
+    UpdateStakingContractConfig {
+        denom: String,
+        unbonding_period: Option<Duration>,
+    },
+    TransferStakingContractOwnership {
+        denom: String,
+        new_owner: String,
+    },
+    /// Applies `change` to the staking contract of every denom.
+    UpdateStakingContracts {
+        denoms: Vec<String>,
+        change: StakingContractChange,
+    },
+    /// Migrates the staking contract of every denom, or of every registered denom when not
+    /// set, to `new_code_id`. A failed migration does not stop the others, each outcome is
+    /// reported in the attributes.
+    MigrateStakingContracts {
+        new_code_id: u64,
+        msg: Binary,
+        denoms: Option<Vec<String>>,
+    },
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -4,4 +4,7 @@ use symphony_interfaces::orchestrator::RegisteredContract;
 
 pub const STAKING_CONTRACTS: Map<&String, RegisteredContract> = Map::new("staking_contracts");
 
+/// Replaced and deregistered contracts, by denom and address.
+pub const RETIRED_STAKING_CONTRACTS: Map<(&str, &str), RegisteredContract> = Map::new("retired_staking_contracts");
+
 pub const REWARDS_CONTRACT: Item<Addr> = Item::new("rewards_contract");
\ No newline at end of file
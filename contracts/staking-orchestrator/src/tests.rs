@@ -1,7 +1,13 @@
-use super::msg::{ExecuteMsg, InstantiateMsg};
-use cosmwasm_std::{coin, Addr, BlockInfo, Coin, DenomUnit, Empty, StdResult, Uint128};
+use super::error::ContractError;
+use super::migrations::{RegisteredContractV0_1, STAKING_CONTRACTS_V0_1};
+use super::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, StakingContractChange};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{coin, to_json_binary, Addr, Binary, BlockInfo, Coin, DenomUnit, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128};
 use cw_multi_test::{App, Contract, ContractWrapper, Executor};
-use symphony_interfaces::orchestrator::{AllTokensStakedBalanceAtHeightResponse, ListStakersByDenomResponse, QueryMsg, StakingContractByDenomResponse};
+use cw_controllers::HooksResponse;
+use cw_ownable::OwnershipError;
+use symphony_interfaces::orchestrator::{AllTokensStakedBalanceAtHeightResponse, ContractStatus, ListStakersByDenomResponse, ListStakingContractsResponse, QueryMsg, RetiredStakingContractsResponse, RewardsContractResponse, StakingContractByDenomResponse, StakingToken, TokenKind};
+use symphony_interfaces::staking::Permission;
 
 const OWNER: &str = "owner";
 const TIME_BETWEEN_BLOCKS: u64 = 5;
@@ -33,7 +39,9 @@ pub fn native_staking_contract() -> Box<dyn Contract<Empty>>{
         native_staking::contract::execute,
         native_staking::contract::instantiate,
         native_staking::contract::query,
-    );
+    )
+        .with_reply(native_staking::contract::reply)
+        .with_migrate(native_staking::contract::migrate);
     Box::new(contract)
 }
 
@@ -42,7 +50,9 @@ pub fn staking_orchestrator_contract() -> Box<dyn Contract<Empty>> {
         super::contract::execute,
         super::contract::instantiate,
         super::contract::query,
-    ).with_reply(crate::contract::reply);
+    )
+        .with_reply(crate::contract::reply)
+        .with_migrate(crate::contract::migrate);
     Box::new(contract)
 }
 
@@ -51,7 +61,7 @@ fn instantiate_orchestrator(app: &mut App, owner: Option<String>) -> Addr {
     let msg = InstantiateMsg { owner };
     app.instantiate_contract(
         orchestrator_code_id,
-        app.api().addr_make(OWNER).into(),
+        app.api().addr_make(OWNER),
         &msg,
         &[],
         "orchestrator",
@@ -100,11 +110,11 @@ pub fn execute_create_staking_contract() {
     let denom_unit = "ustake";
     let msg = ExecuteMsg::CreateStakingContract {
         code_id: staking_code_id,
-        denom_unit: DenomUnit {
+        token: StakingToken::Native(DenomUnit {
             denom: denom_unit.to_string(),
             exponent: 6,
             aliases: vec![],
-        },
+        }),
         unbonding_period: None,
         owner: None,
     };
@@ -125,6 +135,7 @@ pub fn execute_create_staking_contract() {
 
     assert_eq!(contract_data.denom, denom_unit);
     assert_eq!(contract_data.registered_contract.token.denom, denom_unit);
+    assert_eq!(contract_data.registered_contract.kind, TokenKind::Native);
 }
 
 #[test]
@@ -140,11 +151,11 @@ pub fn query_all_staked_tokens() {
     let denom_unit = "ustake";
     let msg = ExecuteMsg::CreateStakingContract {
         code_id: staking_code_id,
-        denom_unit: DenomUnit {
+        token: StakingToken::Native(DenomUnit {
             denom: denom_unit.to_string(),
             exponent: 6,
             aliases: vec![],
-        },
+        }),
         unbonding_period: None,
         owner: None,
     };
@@ -159,11 +170,11 @@ pub fn query_all_staked_tokens() {
     let denom_unit = "ucoin";
     let msg = ExecuteMsg::CreateStakingContract {
         code_id: staking_code_id,
-        denom_unit: DenomUnit {
+        token: StakingToken::Native(DenomUnit {
             denom: denom_unit.to_string(),
             exponent: 6,
             aliases: vec![],
-        },
+        }),
         unbonding_period: None,
         owner: None,
     };
@@ -232,11 +243,11 @@ pub fn query_list_stakers_by_denom() {
     let denom_unit = "ustake";
     let msg = ExecuteMsg::CreateStakingContract {
         code_id: staking_code_id,
-        denom_unit: DenomUnit {
+        token: StakingToken::Native(DenomUnit {
             denom: denom_unit.to_string(),
             exponent: 6,
             aliases: vec![],
-        },
+        }),
         unbonding_period: None,
         owner: None,
     };
@@ -289,3 +300,832 @@ pub fn query_list_stakers_by_denom() {
 
     assert_eq!(stakers.stakers.len(), 1);
 }
+
+#[test]
+pub fn set_rewards_contract_should_register_hook_on_new_staking_contracts() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let rewards_address = app.api().addr_make("rewards");
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+
+    let rewards_contract: RewardsContractResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::RewardsContract {},
+    ).unwrap();
+    assert_eq!(rewards_contract.address, None);
+
+    let msg = ExecuteMsg::SetRewardsContract {
+        address: rewards_address.to_string(),
+    };
+    let err = app.execute_contract(
+        app.api().addr_make("other_user"),
+        orchestrator_contract.clone(),
+        &msg,
+        &[],
+    ).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), OwnershipError::NotOwner.to_string());
+
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &msg,
+        &[],
+    ).unwrap();
+
+    let rewards_contract: RewardsContractResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::RewardsContract {},
+    ).unwrap();
+    assert_eq!(rewards_contract.address, Some(rewards_address.to_string()));
+
+    let staking_code_id = app.store_code(native_staking_contract());
+    let msg = ExecuteMsg::CreateStakingContract {
+        code_id: staking_code_id,
+        token: StakingToken::Native(DenomUnit {
+            denom: "ustake".to_string(),
+            exponent: 6,
+            aliases: vec![],
+        }),
+        unbonding_period: None,
+        owner: Some(owner_address.to_string()),
+    };
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &msg,
+        &[],
+    ).unwrap();
+
+    let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::StakingContractByDenom {
+            denom: "ustake".to_string(),
+        },
+    ).unwrap();
+
+    let hooks: HooksResponse = app.wrap().query_wasm_smart(
+        contract_data.registered_contract.address,
+        &symphony_interfaces::staking::QueryMsg::Hooks {},
+    ).unwrap();
+    assert_eq!(hooks.hooks, vec![rewards_address.to_string()]);
+}
+
+pub fn cw20_staking_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_staking::contract::execute,
+        cw20_staking::contract::instantiate,
+        cw20_staking::contract::query,
+    );
+    Box::new(contract)
+}
+
+pub fn cw20_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+#[test]
+pub fn execute_create_cw20_staking_contract() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+
+    let cw20_code_id = app.store_code(cw20_contract());
+    let token = app.instantiate_contract(cw20_code_id, owner_address.clone(), &cw20_base::msg::InstantiateMsg {
+        name: "Staking Token".to_string(),
+        symbol: "STAKE".to_string(),
+        decimals: 6,
+        initial_balances: vec![cw20::Cw20Coin { address: owner_address.to_string(), amount: Uint128::new(200) }],
+        mint: None,
+        marketing: None,
+    }, &[], "cw20", None).unwrap();
+
+    let staking_code_id = app.store_code(cw20_staking_contract());
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::CreateStakingContract {
+            code_id: staking_code_id,
+            token: StakingToken::Cw20 { address: token.to_string() },
+            unbonding_period: None,
+            owner: None,
+        },
+        &[],
+    ).unwrap();
+
+    let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::StakingContractByDenom {
+            denom: token.to_string(),
+        },
+    ).unwrap();
+    assert_eq!(contract_data.registered_contract.kind, TokenKind::Cw20);
+    assert_eq!(contract_data.registered_contract.token.exponent, 6);
+
+    app.execute_contract(owner_address.clone(), token.clone(), &cw20::Cw20ExecuteMsg::Send {
+        contract: contract_data.registered_contract.address,
+        amount: Uint128::new(100),
+        msg: cosmwasm_std::to_json_binary(&symphony_interfaces::cw20_staking::ReceiveMsg::Stake {}).unwrap(),
+    }, &[]).unwrap();
+
+    next_block(&mut app);
+
+    let all_staked: AllTokensStakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::AllTokensStakedBalanceAtHeight {
+            address: owner_address.to_string(),
+            height: None
+        }
+    ).unwrap();
+    assert_eq!(all_staked.tokens_staked_balance.get(token.as_str()).unwrap().balance, Uint128::new(100));
+
+    // CW20 staking contracts are only used directly
+    let err = app.execute_contract(owner_address.clone(), orchestrator_contract.clone(), &ExecuteMsg::Unstake {
+        denom: token.to_string(),
+        amount: Uint128::new(100),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::RoutingUnsupported { denom: token.to_string() });
+    let err = app.execute_contract(owner_address, orchestrator_contract, &ExecuteMsg::Claim {
+        denoms: vec![token.to_string()],
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::RoutingUnsupported { denom: token.to_string() });
+}
+
+fn instantiate_native_staking(app: &mut App, denom: &str) -> Addr {
+    let staking_code_id = app.store_code(native_staking_contract());
+    app.instantiate_contract(
+        staking_code_id,
+        app.api().addr_make(OWNER),
+        &symphony_interfaces::staking::InstantiateMsg {
+            owner: None,
+            denom_unit: DenomUnit {
+                denom: denom.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            unbonding_period: None,
+            hooks: None,
+            dao: None,
+        },
+        &[],
+        "staking",
+        None,
+    )
+        .unwrap()
+}
+
+#[test]
+pub fn register_replace_and_deregister_staking_contracts() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+    let first = instantiate_native_staking(&mut app, "ustake");
+    let second = instantiate_native_staking(&mut app, "ustake");
+
+    let err = app.execute_contract(
+        app.api().addr_make("other_user"),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::RegisterStakingContract { address: first.to_string() },
+        &[],
+    ).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Ownership(OwnershipError::NotOwner));
+
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::RegisterStakingContract { address: first.to_string() },
+        &[],
+    ).unwrap();
+
+    let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::StakingContractByDenom { denom: "ustake".to_string() },
+    ).unwrap();
+    assert_eq!(contract_data.registered_contract.address, first.to_string());
+    assert_eq!(contract_data.registered_contract.kind, TokenKind::Native);
+    assert_eq!(contract_data.registered_contract.status, ContractStatus::Active);
+    assert_eq!(contract_data.registered_contract.registered_at, app.block_info().time);
+
+    // neither registering nor creating may overwrite the registered denom
+    let err = app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::RegisterStakingContract { address: second.to_string() },
+        &[],
+    ).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::DenomAlreadyRegistered { denom: "ustake".to_string() });
+
+    let staking_code_id = app.store_code(native_staking_contract());
+    let err = app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::CreateStakingContract {
+            code_id: staking_code_id,
+            token: StakingToken::Native(DenomUnit {
+                denom: "ustake".to_string(),
+                exponent: 6,
+                aliases: vec![],
+            }),
+            unbonding_period: None,
+            owner: None,
+        },
+        &[],
+    ).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::DenomAlreadyRegistered { denom: "ustake".to_string() });
+
+    next_block(&mut app);
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::ReplaceStakingContract { address: second.to_string() },
+        &[],
+    ).unwrap();
+
+    let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::StakingContractByDenom { denom: "ustake".to_string() },
+    ).unwrap();
+    assert_eq!(contract_data.registered_contract.address, second.to_string());
+    assert_eq!(contract_data.registered_contract.registered_at, app.block_info().time);
+
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::DeregisterStakingContract { denom: "ustake".to_string() },
+        &[],
+    ).unwrap();
+
+    let err = app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::DeregisterStakingContract { denom: "ustake".to_string() },
+        &[],
+    ).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::DenomNotRegistered { denom: "ustake".to_string() });
+
+    let retired: RetiredStakingContractsResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::RetiredStakingContracts { denom: "ustake".to_string() },
+    ).unwrap();
+    let mut statuses = retired.contracts.into_iter()
+        .map(|contract| (contract.address, contract.status))
+        .collect::<Vec<_>>();
+    statuses.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut expected = vec![
+        (first.to_string(), ContractStatus::Deprecated),
+        (second.to_string(), ContractStatus::Closed),
+    ];
+    expected.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(statuses, expected);
+}
+
+#[test]
+pub fn register_should_require_the_rewards_hook() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let rewards_address = app.api().addr_make("rewards");
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::SetRewardsContract { address: rewards_address.to_string() },
+        &[],
+    ).unwrap();
+
+    // owned by someone else and not notifying the rewards contract
+    let unhooked = instantiate_native_staking(&mut app, "ustake");
+    let err = app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::RegisterStakingContract { address: unhooked.to_string() },
+        &[],
+    ).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::MissingRewardsHook { address: unhooked.to_string() });
+
+    // owned by the orchestrator, which adds the hook
+    let staking_code_id = app.store_code(native_staking_contract());
+    let owned = app.instantiate_contract(
+        staking_code_id,
+        owner_address.clone(),
+        &symphony_interfaces::staking::InstantiateMsg {
+            owner: Some(orchestrator_contract.to_string()),
+            denom_unit: DenomUnit {
+                denom: "ustake".to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            unbonding_period: None,
+            hooks: None,
+            dao: None,
+        },
+        &[],
+        "staking",
+        None,
+    ).unwrap();
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::RegisterStakingContract { address: owned.to_string() },
+        &[],
+    ).unwrap();
+
+    let hooks: HooksResponse = app.wrap().query_wasm_smart(
+        owned,
+        &symphony_interfaces::staking::QueryMsg::Hooks {},
+    ).unwrap();
+    assert_eq!(hooks.hooks, vec![rewards_address.to_string()]);
+}
+
+#[cw_serde]
+struct LegacyInstantiateMsg {
+    contracts: Vec<(String, String)>,
+}
+
+// writes the registry of the first release, as deployed before the current code
+pub fn legacy_orchestrator_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |deps: DepsMut, _env: Env, info: MessageInfo, msg: LegacyInstantiateMsg| -> StdResult<Response> {
+            cw2::set_contract_version(deps.storage, crate::contract::CONTRACT_NAME, "0.1.0")?;
+            cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
+            for (denom, address) in msg.contracts {
+                STAKING_CONTRACTS_V0_1.save(deps.storage, &denom, &RegisteredContractV0_1 {
+                    address,
+                    token: DenomUnit {
+                        denom: denom.clone(),
+                        exponent: 6,
+                        aliases: vec![],
+                    },
+                })?;
+            }
+            Ok(Response::new())
+        },
+        |_deps: Deps, _env: Env, _msg: Empty| -> StdResult<Binary> {
+            to_json_binary(&Empty {})
+        },
+    );
+    Box::new(contract)
+}
+
+#[test]
+pub fn migrate_should_upgrade_v0_1_registry() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staking_contract = instantiate_native_staking(&mut app, "ustake");
+
+    let legacy_code_id = app.store_code(legacy_orchestrator_contract());
+    let orchestrator_contract = app.instantiate_contract(
+        legacy_code_id,
+        owner_address.clone(),
+        &LegacyInstantiateMsg {
+            contracts: vec![("ustake".to_string(), staking_contract.to_string())],
+        },
+        &[],
+        "orchestrator",
+        Some(owner_address.to_string()),
+    ).unwrap();
+
+    let orchestrator_code_id = app.store_code(staking_orchestrator_contract());
+    app.migrate_contract(owner_address, orchestrator_contract.clone(), &MigrateMsg {}, orchestrator_code_id).unwrap();
+
+    let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract,
+        &QueryMsg::StakingContractByDenom { denom: "ustake".to_string() },
+    ).unwrap();
+    assert_eq!(contract_data.registered_contract.address, staking_contract.to_string());
+    assert_eq!(contract_data.registered_contract.kind, TokenKind::Native);
+    assert_eq!(contract_data.registered_contract.status, ContractStatus::Active);
+    assert_eq!(contract_data.registered_contract.registered_at, app.block_info().time);
+}
+
+#[cw_serde]
+enum LegacyStakingQueryMsg {
+    Config {},
+    TotalStakedAtHeight { height: Option<u64> },
+}
+
+#[cw_serde]
+struct LegacyStakingConfig {
+    staking_token: DenomUnit,
+    unstaking_duration: Option<cw_utils::Duration>,
+}
+
+// answers with the config of the first release, without the fields added since
+pub fn legacy_staking_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |deps: DepsMut, _env: Env, _info: MessageInfo, msg: DenomUnit| -> StdResult<Response> {
+            cw_storage_plus::Item::new("denom").save(deps.storage, &msg)?;
+            Ok(Response::new())
+        },
+        |deps: Deps, env: Env, msg: LegacyStakingQueryMsg| -> StdResult<Binary> {
+            match msg {
+                LegacyStakingQueryMsg::Config {} => to_json_binary(&LegacyStakingConfig {
+                    staking_token: cw_storage_plus::Item::new("denom").load(deps.storage)?,
+                    unstaking_duration: None,
+                }),
+                LegacyStakingQueryMsg::TotalStakedAtHeight { height } => {
+                    to_json_binary(&symphony_interfaces::staking::TotalStakedAtHeightResponse {
+                        total: Uint128::zero(),
+                        height: height.unwrap_or(env.block.height),
+                    })
+                }
+            }
+        },
+    );
+    Box::new(contract)
+}
+
+fn instantiate_legacy_staking(app: &mut App, denom: &str) -> Addr {
+    let legacy_code_id = app.store_code(legacy_staking_contract());
+    app.instantiate_contract(
+        legacy_code_id,
+        app.api().addr_make(OWNER),
+        &DenomUnit {
+            denom: denom.to_string(),
+            exponent: 6,
+            aliases: vec![],
+        },
+        &[],
+        "staking",
+        None,
+    ).unwrap()
+}
+
+#[test]
+pub fn register_should_accept_v0_1_staking_contracts() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+    let staking_contract = instantiate_legacy_staking(&mut app, "ustake");
+
+    app.execute_contract(
+        owner_address,
+        orchestrator_contract.clone(),
+        &ExecuteMsg::RegisterStakingContract { address: staking_contract.to_string() },
+        &[],
+    ).unwrap();
+
+    let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract,
+        &QueryMsg::StakingContractByDenom { denom: "ustake".to_string() },
+    ).unwrap();
+    assert_eq!(contract_data.registered_contract.address, staking_contract.to_string());
+    assert_eq!(contract_data.registered_contract.kind, TokenKind::Native);
+}
+
+#[test]
+pub fn query_list_staking_contracts_should_list_v0_1_staking_contracts() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+
+    for staking_contract in [instantiate_legacy_staking(&mut app, "uold"), instantiate_native_staking(&mut app, "unew")] {
+        app.execute_contract(
+            owner_address.clone(),
+            orchestrator_contract.clone(),
+            &ExecuteMsg::RegisterStakingContract { address: staking_contract.to_string() },
+            &[],
+        ).unwrap();
+    }
+
+    let page: ListStakingContractsResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract,
+        &QueryMsg::ListStakingContracts { start_after: None, limit: None },
+    ).unwrap();
+    let denoms = page.contracts.iter().map(|contract| contract.denom.as_str()).collect::<Vec<_>>();
+    assert_eq!(denoms, vec!["unew", "uold"]);
+    assert_eq!(page.contracts[1].config.lock_tiers, vec![]);
+    assert_eq!(page.contracts[1].config.delegation, None);
+}
+
+#[test]
+pub fn query_list_staking_contracts_should_page_by_denom() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+
+    let staking_code_id = app.store_code(native_staking_contract());
+    let denoms = (0..32).map(|i| format!("udenom{i:02}")).collect::<Vec<_>>();
+    for denom in &denoms {
+        app.execute_contract(
+            owner_address.clone(),
+            orchestrator_contract.clone(),
+            &ExecuteMsg::CreateStakingContract {
+                code_id: staking_code_id,
+                token: StakingToken::Native(DenomUnit {
+                    denom: denom.clone(),
+                    exponent: 6,
+                    aliases: vec![],
+                }),
+                unbonding_period: Some(cw_utils::Duration::Time(10)),
+                owner: None,
+            },
+            &[],
+        ).unwrap();
+    }
+
+    let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::StakingContractByDenom { denom: "udenom00".to_string() },
+    ).unwrap();
+    mint_native(&mut app, owner_address.to_string(), "udenom00".to_string(), 100);
+    stake_tokens(&mut app, Uint128::new(100), "udenom00", Addr::unchecked(contract_data.registered_contract.address));
+    next_block(&mut app);
+
+    let page: ListStakingContractsResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::ListStakingContracts { start_after: None, limit: None },
+    ).unwrap();
+    assert_eq!(page.contracts.len(), 10);
+    assert_eq!(page.contracts[0].denom, "udenom00");
+    assert_eq!(page.contracts[0].registered_contract.token.denom, "udenom00");
+    assert_eq!(page.contracts[0].config.unstaking_duration, Some(cw_utils::Duration::Time(10)));
+    assert_eq!(page.contracts[0].total_staked.total, Uint128::new(100));
+    assert_eq!(page.contracts[1].total_staked.total, Uint128::zero());
+
+    let page: ListStakingContractsResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::ListStakingContracts { start_after: Some("udenom00".to_string()), limit: Some(100) },
+    ).unwrap();
+    assert_eq!(page.contracts.len(), 30);
+    assert_eq!(
+        page.contracts.iter().map(|contract| contract.denom.clone()).collect::<Vec<_>>(),
+        denoms[1..31].to_vec(),
+    );
+}
+
+#[test]
+pub fn stake_unstake_and_claim_should_route_by_denom() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker = app.api().addr_make("staker");
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+
+    let staking_code_id = app.store_code(native_staking_contract());
+    let mut staking_contracts = vec![];
+    for denom in ["ucoin", "ustake"] {
+        app.execute_contract(
+            owner_address.clone(),
+            orchestrator_contract.clone(),
+            &ExecuteMsg::CreateStakingContract {
+                code_id: staking_code_id,
+                token: StakingToken::Native(DenomUnit {
+                    denom: denom.to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                }),
+                unbonding_period: Some(cw_utils::Duration::Time(5)),
+                owner: None,
+            },
+            &[],
+        ).unwrap();
+        let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+            orchestrator_contract.clone(),
+            &QueryMsg::StakingContractByDenom { denom: denom.to_string() },
+        ).unwrap();
+        staking_contracts.push(Addr::unchecked(contract_data.registered_contract.address));
+        mint_native(&mut app, staker.to_string(), denom.to_string(), 1_000);
+    }
+    mint_native(&mut app, staker.to_string(), "uother".to_string(), 1_000);
+
+    let err = app.execute_contract(staker.clone(), orchestrator_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(100, "uother"),
+    ]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::DenomNotRegistered { denom: "uother".to_string() });
+
+    app.execute_contract(staker.clone(), orchestrator_contract.clone(), &ExecuteMsg::Stake {}, &[
+        coin(100, "ucoin"),
+        coin(200, "ustake"),
+    ]).unwrap();
+    next_block(&mut app);
+
+    let all_staked: AllTokensStakedBalanceAtHeightResponse = app.wrap().query_wasm_smart(
+        orchestrator_contract.clone(),
+        &QueryMsg::AllTokensStakedBalanceAtHeight { address: staker.to_string(), height: None },
+    ).unwrap();
+    assert_eq!(all_staked.tokens_staked_balance.get("ucoin").unwrap().balance, Uint128::new(100));
+    assert_eq!(all_staked.tokens_staked_balance.get("ustake").unwrap().balance, Uint128::new(200));
+
+    // the staker has to approve the orchestrator in each staking contract first
+    let unstake = ExecuteMsg::Unstake { denom: "ustake".to_string(), amount: Uint128::new(150) };
+    let err = app.execute_contract(staker.clone(), orchestrator_contract.clone(), &unstake, &[]).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        native_staking::error::ContractError::NotApproved { permission: Permission::Unstake }.to_string(),
+    );
+
+    for staking_contract in &staking_contracts {
+        app.execute_contract(staker.clone(), staking_contract.clone(), &symphony_interfaces::staking::ExecuteMsg::Approve {
+            operator: orchestrator_contract.to_string(),
+            expires: None,
+            permissions: vec![Permission::Unstake, Permission::Claim],
+        }, &[]).unwrap();
+    }
+    app.execute_contract(staker.clone(), orchestrator_contract.clone(), &unstake, &[]).unwrap();
+    app.execute_contract(staker.clone(), orchestrator_contract.clone(), &ExecuteMsg::Unstake {
+        denom: "ucoin".to_string(),
+        amount: Uint128::new(100),
+    }, &[]).unwrap();
+
+    next_block(&mut app);
+    app.execute_contract(staker.clone(), orchestrator_contract.clone(), &ExecuteMsg::Claim {
+        denoms: vec!["ucoin".to_string(), "ustake".to_string()],
+    }, &[]).unwrap();
+
+    assert_eq!(app.wrap().query_balance(&staker, "ucoin").unwrap().amount, Uint128::new(1_000));
+    assert_eq!(app.wrap().query_balance(&staker, "ustake").unwrap().amount, Uint128::new(950));
+    assert_eq!(app.wrap().query_balance(&orchestrator_contract, "ustake").unwrap().amount, Uint128::zero());
+}
+
+#[test]
+pub fn owner_should_administer_child_staking_contracts() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let new_owner = app.api().addr_make("new_owner");
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+
+    let staking_code_id = app.store_code(native_staking_contract());
+    let mut staking_contracts = vec![];
+    for denom in ["ucoin", "ustake"] {
+        app.execute_contract(
+            owner_address.clone(),
+            orchestrator_contract.clone(),
+            &ExecuteMsg::CreateStakingContract {
+                code_id: staking_code_id,
+                token: StakingToken::Native(DenomUnit {
+                    denom: denom.to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                }),
+                unbonding_period: None,
+                owner: None,
+            },
+            &[],
+        ).unwrap();
+        let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+            orchestrator_contract.clone(),
+            &QueryMsg::StakingContractByDenom { denom: denom.to_string() },
+        ).unwrap();
+        staking_contracts.push(contract_data.registered_contract.address);
+    }
+    let unbonding_period = |app: &App, staking_contract: &String| {
+        let config: symphony_interfaces::staking::ConfigResponse = app.wrap().query_wasm_smart(
+            staking_contract,
+            &symphony_interfaces::staking::QueryMsg::Config {},
+        ).unwrap();
+        config.unstaking_duration
+    };
+
+    let update_config = ExecuteMsg::UpdateStakingContractConfig {
+        denom: "ustake".to_string(),
+        unbonding_period: Some(cw_utils::Duration::Time(20)),
+    };
+    let err = app.execute_contract(new_owner.clone(), orchestrator_contract.clone(), &update_config, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Ownership(OwnershipError::NotOwner));
+
+    app.execute_contract(owner_address.clone(), orchestrator_contract.clone(), &update_config, &[]).unwrap();
+    assert_eq!(unbonding_period(&app, &staking_contracts[0]), None);
+    assert_eq!(unbonding_period(&app, &staking_contracts[1]), Some(cw_utils::Duration::Time(20)));
+
+    app.execute_contract(owner_address.clone(), orchestrator_contract.clone(), &ExecuteMsg::UpdateStakingContracts {
+        denoms: vec!["ucoin".to_string(), "ustake".to_string()],
+        change: StakingContractChange::UpdateConfig { unbonding_period: Some(cw_utils::Duration::Time(30)) },
+    }, &[]).unwrap();
+    for staking_contract in &staking_contracts {
+        assert_eq!(unbonding_period(&app, staking_contract), Some(cw_utils::Duration::Time(30)));
+    }
+
+    let err = app.execute_contract(owner_address.clone(), orchestrator_contract.clone(), &ExecuteMsg::UpdateStakingContracts {
+        denoms: vec!["ucoin".to_string(), "uother".to_string()],
+        change: StakingContractChange::UpdateConfig { unbonding_period: None },
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::DenomNotRegistered { denom: "uother".to_string() });
+
+    app.execute_contract(owner_address, orchestrator_contract.clone(), &ExecuteMsg::TransferStakingContractOwnership {
+        denom: "ucoin".to_string(),
+        new_owner: new_owner.to_string(),
+    }, &[]).unwrap();
+    let ownership: cw_ownable::Ownership<String> = app.wrap().query_wasm_smart(
+        &staking_contracts[0],
+        &symphony_interfaces::staking::QueryMsg::Ownership {},
+    ).unwrap();
+    assert_eq!(ownership.owner, Some(orchestrator_contract.to_string()));
+    assert_eq!(ownership.pending_owner, Some(new_owner.to_string()));
+}
+
+#[test]
+pub fn migrate_staking_contracts_should_report_each_contract() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let orchestrator_contract = instantiate_orchestrator(
+        &mut app,
+        Some(owner_address.clone().into()),
+    );
+
+    let staking_code_id = app.store_code(native_staking_contract());
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::CreateStakingContract {
+            code_id: staking_code_id,
+            token: StakingToken::Native(DenomUnit {
+                denom: "ucoin".to_string(),
+                exponent: 6,
+                aliases: vec![],
+            }),
+            unbonding_period: None,
+            owner: None,
+        },
+        &[],
+    ).unwrap();
+    // adopted without the orchestrator as admin, so it cannot be migrated
+    let adopted = instantiate_native_staking(&mut app, "ustake");
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_contract.clone(),
+        &ExecuteMsg::RegisterStakingContract { address: adopted.to_string() },
+        &[],
+    ).unwrap();
+
+    let registered_contract = |app: &App, denom: &str| {
+        let contract_data: StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+            orchestrator_contract.clone(),
+            &QueryMsg::StakingContractByDenom { denom: denom.to_string() },
+        ).unwrap();
+        contract_data.registered_contract
+    };
+    let created = registered_contract(&app, "ucoin");
+    assert_eq!(created.code_id, Some(staking_code_id));
+    let adopted_code_id = app.wrap().query_wasm_contract_info(&adopted).unwrap().code_id;
+    assert_eq!(registered_contract(&app, "ustake").code_id, Some(adopted_code_id));
+    assert_eq!(created.version, Some(env!("CARGO_PKG_VERSION").to_string()));
+
+    let new_code_id = app.store_code(native_staking_contract());
+    let msg = ExecuteMsg::MigrateStakingContracts {
+        new_code_id,
+        msg: to_json_binary(&native_staking::msg::MigrateMsg {}).unwrap(),
+        denoms: None,
+    };
+    let err = app.execute_contract(app.api().addr_make("other_user"), orchestrator_contract.clone(), &msg, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::Ownership(OwnershipError::NotOwner));
+
+    let err = app.execute_contract(owner_address.clone(), orchestrator_contract.clone(), &ExecuteMsg::MigrateStakingContracts {
+        new_code_id,
+        msg: to_json_binary(&native_staking::msg::MigrateMsg {}).unwrap(),
+        denoms: Some(vec!["uother".to_string()]),
+    }, &[]).unwrap_err();
+    assert_eq!(err.downcast::<ContractError>().unwrap(), ContractError::DenomNotRegistered { denom: "uother".to_string() });
+
+    let res = app.execute_contract(owner_address, orchestrator_contract.clone(), &msg, &[]).unwrap();
+    let results = res.events.iter()
+        .filter(|event| event.attributes.iter().any(|attr| attr.key == "action" && attr.value == "migrate_staking_contract"))
+        .map(|event| {
+            let attr = |key: &str| event.attributes.iter().find(|attr| attr.key == key).unwrap().value.clone();
+            (attr("denom"), attr("result"))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(results, vec![
+        ("ucoin".to_string(), "success".to_string()),
+        ("ustake".to_string(), "failure".to_string()),
+    ]);
+
+    assert_eq!(registered_contract(&app, "ucoin").code_id, Some(new_code_id));
+    assert_eq!(registered_contract(&app, "ustake").code_id, Some(adopted_code_id));
+    assert_eq!(app.wrap().query_wasm_contract_info(&created.address).unwrap().code_id, new_code_id);
+}
@@ -1,19 +1,26 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use crate::error::ContractError;
-use crate::msg::{AllUserStatesResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, IsPausedResponse, ListPoolStatesResponse, PoolStateResponse, QueryMsg, UserStateResponse};
-use crate::state::{Config, PoolState, RewardsDistributionByToken, RewardsRecord, UserState, CONFIG, PAUSED, POOL_STATE, USER_STATE};
-use cosmwasm_std::{to_json_binary, Addr, Binary, BlockInfo, DenomUnit, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint128, Uint64};
+use crate::migrations::MIGRATIONS;
+use crate::msg::{AllUserStatesResponse, ConfigResponse, DenomSolvency, EmissionScheduleResponse, ExecuteMsg, InstantiateMsg, IsPausedResponse, ListEmissionSchedulesResponse, ListPoolStatesResponse, MigrateMsg, PoolClaim, PoolRemainders, PoolStateResponse, QueryMsg, RemaindersResponse, SimulateClaimResponse, SolvencyResponse, UserStateResponse};
+use crate::state::{Config, EmissionSchedule, EmptyPoolPolicy, PoolState, RetiredStakingContract, RewardsDistributionByToken, RewardsRecord, UserState, CONFIG, EMISSION_SCHEDULES, EMISSION_SCHEDULE_COUNT, PAUSED, POOL_STATE, USER_STATE};
+use cosmwasm_std::{coin, to_json_binary, Addr, BankMsg, Binary, BlockInfo, Coin, Coins, Decimal, DenomUnit, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult, Storage, Timestamp, Uint128, Uint64};
 use cw2::set_contract_version;
-use symphony_interfaces::staking::StakerBalanceResponse;
+use symphony_utils::migrate::migrate_contract;
+use cw_controllers::HooksResponse;
+use symphony_interfaces::orchestrator::IsRewardedResponse;
+use symphony_interfaces::staking::{SlashHookMsg, StakeChangedHookMsg};
 
 pub(crate) const CONTRACT_NAME: &str = "crates.io:symphony-staking-rewards";
 pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const WEIGHT_TOTAL: u64 = 100_000;
 
+type PoolStates = BTreeMap<String, PoolState>;
+type EmissionSchedules = Vec<(u64, EmissionSchedule)>;
+
 fn assert_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
     let paused = PAUSED.load(storage)?;
     if paused {
@@ -36,20 +43,18 @@ pub fn instantiate(
 
     let orchestrator_addr = deps.api.addr_validate(&msg.staking_orchestrator_addr)?;
     validate_distribution(&msg.rewards_distribution)?;
+    validate_reward_tokens(&msg.reward_tokens)?;
 
     let config = Config {
         staking_orchestrator_addr: orchestrator_addr.clone(),
-        reward_token: msg.reward_token.clone(),
+        reward_tokens: msg.reward_tokens.clone(),
         rewards_distribution: msg.rewards_distribution.clone(),
+        empty_pool_policy: msg.empty_pool_policy.unwrap_or_default(),
     };
     CONFIG.save(deps.storage, &config)?;
 
-    let pool_states = init_pool_state(
-        &msg.rewards_distribution.iter().map(|x| x.denom.clone()).collect::<Vec<DenomUnit>>(),
-        &env.block,
-    );
-
-    for pool_state in pool_states {
+    for distro in &msg.rewards_distribution {
+        let pool_state = init_pool_state(&distro.denom, &env.block);
         POOL_STATE.save(deps.storage, &pool_state.denom.denom, &pool_state, env.block.height)?;
     }
 
@@ -59,18 +64,24 @@ pub fn instantiate(
         .add_attribute("action", "instantiate")
         .add_attribute("owner", owner)
         .add_attribute("staking_orchestrator_addr", orchestrator_addr)
-        .add_attribute("reward_token", msg.reward_token.denom))
+        .add_attribute("reward_tokens", reward_denoms(&msg.reward_tokens))
+        .add_attribute("empty_pool_policy", config.empty_pool_policy.to_string()))
 }
 
-fn init_pool_state(denoms: &[DenomUnit], block_info: &BlockInfo) -> Vec<PoolState> {
-    denoms.iter().map(|denom| PoolState {
+fn init_pool_state(denom: &DenomUnit, block_info: &BlockInfo) -> PoolState {
+    PoolState {
         denom: denom.clone(),
-        total_rewards: Uint128::zero(),
+        rewards: BTreeMap::new(),
         block_height: Uint64::from(block_info.height),
-    }).collect()
+        active: true,
+        exchange_rate: Decimal::one(),
+        staking_contract: None,
+        followed_from: BTreeMap::new(),
+        retired_contracts: vec![],
+    }
 }
 
-fn validate_distribution(distribution: &Vec<RewardsDistributionByToken>) -> Result<Uint64, ContractError> {
+fn validate_distribution(distribution: &[RewardsDistributionByToken]) -> Result<Uint64, ContractError> {
     let total_weight = distribution
         .iter()
         .fold(Uint64::zero(), |acc, x| acc + x.weight);
@@ -82,6 +93,17 @@ fn validate_distribution(distribution: &Vec<RewardsDistributionByToken>) -> Resu
     Ok(total_weight)
 }
 
+fn validate_reward_tokens(reward_tokens: &[DenomUnit]) -> Result<(), ContractError> {
+    if reward_tokens.is_empty() {
+        return Err(ContractError::NoRewardTokens {});
+    }
+    Ok(())
+}
+
+fn reward_denoms(reward_tokens: &[DenomUnit]) -> String {
+    reward_tokens.iter().map(|token| token.denom.as_str()).collect::<Vec<&str>>().join(",")
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut,
                env: Env,
@@ -91,13 +113,28 @@ pub fn execute(deps: DepsMut,
         ExecuteMsg::UpdateOwnership(action) => execute_update_owner(deps, info, env, action),
         ExecuteMsg::UpdateConfig {
             staking_orchestrator_addr,
-            reward_token,
+            reward_tokens,
+            rewards_distribution,
+            empty_pool_policy,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            staking_orchestrator_addr,
+            reward_tokens,
             rewards_distribution,
-        } => execute_update_config(deps, info, staking_orchestrator_addr, reward_token, rewards_distribution),
+            empty_pool_policy,
+        ),
         ExecuteMsg::DistributeRewards {} => execute_distribute_rewards(deps, env, info),
         ExecuteMsg::ClaimRewards {} => execute_claim_rewards(deps, env, info),
         ExecuteMsg::Pause {} => execute_pause(deps, info),
         ExecuteMsg::Unpause {} => execute_unpause(deps, info),
+        ExecuteMsg::StakeChangedHook(hook) => execute_stake_changed_hook(deps, env, info, hook),
+        ExecuteMsg::SlashHook(hook) => execute_slash_hook(deps, env, info, hook),
+        ExecuteMsg::CreateEmissionSchedule { start, end, total_amount } => {
+            execute_create_emission_schedule(deps, env, info, start, end, total_amount)
+        }
+        ExecuteMsg::CancelEmissionSchedule { id } => execute_cancel_emission_schedule(deps, env, info, id),
     }
 }
 
@@ -112,229 +149,1113 @@ fn execute_update_owner(
 }
 
 fn execute_update_config(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     staking_orchestrator_addr: Option<String>,
-    reward_token: Option<DenomUnit>,
+    reward_tokens: Option<Vec<DenomUnit>>,
     rewards_distribution: Option<Vec<RewardsDistributionByToken>>,
+    empty_pool_policy: Option<EmptyPoolPolicy>,
 ) -> Result<Response, ContractError> {
     cw_ownable::assert_owner(deps.storage, &info.sender)?;
 
     let mut config = crate::state::CONFIG.load(deps.storage)?;
+
+    // elapsed emissions are split with the weights that were in effect
+    let mut pool_states = process_emissions(deps.branch(), &env, &config)?;
+
     if let Some(staking_orchestrator_addr) = staking_orchestrator_addr {
         config.staking_orchestrator_addr = deps.api.addr_validate(&staking_orchestrator_addr)?;
     };
 
-    if let Some(reward_token) = reward_token {
-        config.reward_token = reward_token;
+    if let Some(reward_tokens) = reward_tokens {
+        validate_reward_tokens(&reward_tokens)?;
+        config.reward_tokens = reward_tokens;
     };
 
+    if let Some(empty_pool_policy) = empty_pool_policy {
+        config.empty_pool_policy = empty_pool_policy;
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute("staking_orchestrator_addr", config.staking_orchestrator_addr.clone())
+        .add_attribute("reward_tokens", reward_denoms(&config.reward_tokens))
+        .add_attribute("empty_pool_policy", config.empty_pool_policy.to_string());
+
     if let Some(rewards_distribution) = rewards_distribution {
         validate_distribution(&rewards_distribution)?;
-        config.rewards_distribution = rewards_distribution;
+
+        let (added, sunset, returned) = update_pools(
+            deps.as_ref(),
+            &mut config,
+            &mut pool_states,
+            rewards_distribution,
+            &env,
+        )?;
+        save_pool_states(deps.storage, &pool_states, env.block.height)?;
+
+        response = response
+            .add_attribute("added_pools", added.join(","))
+            .add_attribute("sunset_pools", sunset.join(","));
+
+        if !returned.is_empty() {
+            response = response
+                .add_attribute("returned", returned.to_string())
+                .add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: returned.into_vec(),
+                });
+        }
     }
 
     CONFIG.save(deps.storage, &config)?;
-    Ok(Response::new()
-        .add_attribute("action", "update_config")
-        .add_attribute("staking_orchestrator_addr", config.staking_orchestrator_addr)
-        .add_attribute("reward_token", config.reward_token.denom)
-    )
+    Ok(response)
+}
+
+/// Moves the pools to a new rewards distribution. Pools of new denoms, which must have a
+/// staking contract registered in the orchestrator that notifies this contract of stake
+/// changes, are initialized or reactivated. Pools
+/// left out are sunset: their accumulator stops growing, so users can still claim what
+/// they accrued, and the rewards queued in them are split between the new pools.
+/// Returns the added and sunset denoms, and the rewards to send back to the owner.
+fn update_pools(
+    deps: Deps,
+    config: &mut Config,
+    pool_states: &mut PoolStates,
+    rewards_distribution: Vec<RewardsDistributionByToken>,
+    env: &Env,
+) -> Result<(Vec<String>, Vec<String>, Coins), ContractError> {
+    let block = &env.block;
+    let mut added = vec![];
+    for distro in &rewards_distribution {
+        let denom = &distro.denom.denom;
+        if pool_states.get(denom).is_some_and(|pool_state| pool_state.active) {
+            continue;
+        }
+
+        let staking_contract = registered_staking_contract(deps, config, denom)?
+            .ok_or_else(|| ContractError::UnregisteredPoolDenom { denom: denom.clone() })?;
+        if !is_hooked(deps, env, &staking_contract)? {
+            return Err(ContractError::UnhookedPoolDenom { denom: denom.clone() });
+        }
+
+        let pool_state = pool_states
+            .entry(denom.clone())
+            .or_insert_with(|| init_pool_state(&distro.denom, block));
+        pool_state.active = true;
+        pool_state.block_height = Uint64::from(block.height);
+        added.push(denom.clone());
+    }
+
+    let mut sunset = vec![];
+    let mut queued_rewards = Coins::default();
+    for (denom, pool_state) in pool_states.iter_mut() {
+        if !pool_state.active || rewards_distribution.iter().any(|distro| &distro.denom.denom == denom) {
+            continue;
+        }
+
+        for (reward_denom, pool_rewards) in pool_state.rewards.iter_mut() {
+            if pool_rewards.remainder.is_zero() {
+                continue;
+            }
+            queued_rewards.add(coin(pool_rewards.remainder.u128(), reward_denom))?;
+            pool_rewards.total_rewards = pool_rewards.total_rewards.checked_sub(pool_rewards.remainder)?;
+            pool_rewards.remainder = Uint128::zero();
+        }
+
+        pool_state.active = false;
+        pool_state.block_height = Uint64::from(block.height);
+        sunset.push(denom.clone());
+    }
+
+    config.rewards_distribution = rewards_distribution;
+    follow_registered_contracts(deps, env, config, pool_states)?;
+
+    let returned = if queued_rewards.is_empty() {
+        Coins::default()
+    } else {
+        add_rewards_to_pools(deps, config, pool_states, &queued_rewards, block, &config.empty_pool_policy, None)?.returned
+    };
+
+    Ok((added, sunset, returned))
 }
 
 fn execute_distribute_rewards(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
     assert_not_paused(deps.storage)?;
     let config = crate::state::CONFIG.load(deps.storage)?;
 
-    let total_rewards_to_distribute = info.funds.iter().find(|coin| coin.denom == config.reward_token.denom)
-        .map(|coin| coin.amount)
-        .unwrap_or_default();
+    let mut total_rewards_to_distribute = Coins::default();
+    for fund in info.funds {
+        if !config.reward_tokens.iter().any(|token| token.denom == fund.denom) {
+            return Err(ContractError::InvalidRewardDenom { denom: fund.denom });
+        }
+        total_rewards_to_distribute.add(fund)?;
+    }
 
-    if total_rewards_to_distribute.is_zero() {
+    if total_rewards_to_distribute.is_empty() {
         return Err(ContractError::NoRewardsToDistribute {});
     }
 
+    let mut pool_states = process_emissions(deps.branch(), &env, &config)?;
+    let PoolsCredit { returned, empty_pools, applied_policy } = add_rewards_to_pools(
+        deps.as_ref(),
+        &config,
+        &mut pool_states,
+        &total_rewards_to_distribute,
+        &env.block,
+        &config.empty_pool_policy,
+        None,
+    )?;
+
+    let denoms = total_rewards_to_distribute.denoms();
+    assert_solvent(deps.as_ref(), &env, &pool_states, &denoms, &returned)?;
+    save_pool_states(deps.storage, &pool_states, env.block.height)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "distribute_rewards")
+        .add_attribute("total_rewards_to_distribute", total_rewards_to_distribute.to_string());
+
+    if !empty_pools.is_empty() {
+        response = response
+            .add_attribute("empty_pool_policy", applied_policy.to_string())
+            .add_attribute("empty_pools", empty_pools.join(","));
+    }
+
+    if !returned.is_empty() {
+        response = response
+            .add_attribute("returned", returned.to_string())
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: returned.into_vec(),
+            });
+    }
+
+    Ok(response)
+}
+
+fn load_pool_states(storage: &dyn Storage) -> StdResult<PoolStates> {
+    POOL_STATE
+        .range(storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect()
+}
+
+fn save_pool_states(
+    storage: &mut dyn Storage,
+    pool_states: &PoolStates,
+    height: u64,
+) -> StdResult<()> {
+    for (denom, pool_state) in pool_states {
+        POOL_STATE.save(storage, denom, pool_state, height)?;
+    }
+    Ok(())
+}
+
+/// Splits `amount` proportionally to `weights`. The rounding leftover goes to the
+/// heaviest pool so the shares always add up to `amount`.
+fn split_by_weight(amount: Uint128, weights: &[Uint64]) -> StdResult<Vec<Uint128>> {
+    let total_weight = weights.iter().try_fold(Uint64::zero(), |acc, weight| acc.checked_add(*weight))?;
+    if total_weight.is_zero() {
+        return Ok(vec![Uint128::zero(); weights.len()]);
+    }
+
+    let mut shares = weights
+        .iter()
+        .map(|weight| amount.multiply_ratio(*weight, total_weight))
+        .collect::<Vec<Uint128>>();
+
+    let distributed = shares.iter().try_fold(Uint128::zero(), |acc, share| acc.checked_add(*share))?;
+    let heaviest = weights
+        .iter()
+        .enumerate()
+        .max_by(|(i, a), (j, b)| a.cmp(b).then(j.cmp(i)))
+        .map(|(i, _)| i);
+
+    if let Some(i) = heaviest {
+        shares[i] = shares[i].checked_add(amount.checked_sub(distributed)?)?;
+    }
+
+    Ok(shares)
+}
+
+/// Staked units held by `staked` tokens at the pool exchange rate, rounded down.
+fn staked_units(staked: Uint128, exchange_rate: Decimal) -> Result<Uint128, ContractError> {
+    Ok(staked.checked_div_floor(exchange_rate)?)
+}
+
+/// Adds `amount` to the pool ledger of `reward_denom` and raises its accumulator over the
+/// pool's total stake. Whatever the accumulator cannot represent is kept as the pool
+/// remainder for the next distribution.
+fn credit_pool(
+    pool_state: &mut PoolState,
+    reward_denom: &str,
+    amount: Uint128,
+    total_staked: Uint128,
+) -> Result<(), ContractError> {
+    // rounded up so the units settled for every staker never exceed the total
+    let total_units = total_staked.checked_div_ceil(pool_state.exchange_rate)?;
+
+    let pool_rewards = pool_state.rewards.entry(reward_denom.to_string()).or_default();
+    pool_rewards.total_rewards = pool_rewards.total_rewards.checked_add(amount)?;
+
+    let to_credit = pool_rewards.remainder.checked_add(amount)?;
+
+    // rewards queued in a pool without stakers wait for the next distribution
+    if total_units.is_zero() {
+        pool_rewards.remainder = to_credit;
+        return Ok(());
+    }
+
+    let reward_per_token = Decimal::checked_from_ratio(to_credit, total_units)?;
+    let credited = total_units.checked_mul_floor(reward_per_token)?;
+
+    pool_rewards.reward_per_token = pool_rewards.reward_per_token.checked_add(reward_per_token)?;
+    pool_rewards.remainder = to_credit.checked_sub(credited)?;
+
+    Ok(())
+}
+
+/// Stake change reported by a hook. The staking contract balances already include it, the
+/// pools not yet.
+struct StakeChange<'a> {
+    staking_contract: &'a Addr,
+    hook: &'a StakeChangedHookMsg,
+}
+
+/// Outcome of splitting rewards between the pools.
+struct PoolsCredit {
+    /// Rewards to send back to the distributor.
+    returned: Coins,
+    /// Weighted pools without stakers.
+    empty_pools: Vec<String>,
+    /// Policy applied to the shares of `empty_pools`.
+    applied_policy: EmptyPoolPolicy,
+}
+
+/// Splits every reward coin between the pools by their distribution weight, handling the
+/// shares of pools without stakers with `empty_pool_policy`. Rewards that accrued before
+/// `stake_change` are credited over the total staked before it.
+fn add_rewards_to_pools(
+    deps: Deps,
+    config: &Config,
+    pool_states: &mut PoolStates,
+    rewards: &Coins,
+    block: &BlockInfo,
+    empty_pool_policy: &EmptyPoolPolicy,
+    stake_change: Option<&StakeChange>,
+) -> Result<PoolsCredit, ContractError> {
+    let mut pools = vec![];
     for distro in &config.rewards_distribution {
-        let denom_rewards = total_rewards_to_distribute
-            .checked_mul(Uint128::from(distro.weight))?
-            .checked_div(Uint128::from(WEIGHT_TOTAL))?;
+        let staking_contract = pool_states
+            .get(&distro.denom.denom)
+            .and_then(|pool_state| pool_state.staking_contract.as_ref());
+        let total_staked = match staking_contract {
+            Some(staking_contract) => {
+                let total_staked = query_total_staked(deps, staking_contract, block)?;
+                match stake_change {
+                    Some(change) if change.staking_contract == staking_contract && change.hook.denom == distro.denom.denom => {
+                        total_staked.checked_sub(change.hook.new_balance)?.checked_add(change.hook.old_balance)?
+                    }
+                    _ => total_staked,
+                }
+            }
+            // a deregistered denom has no stakers left to credit
+            None => Uint128::zero(),
+        };
+        pools.push((distro.denom.denom.clone(), distro.weight, total_staked));
+    }
 
-        if denom_rewards.is_zero() {
-            continue;
+    let staked_pools = pools
+        .iter()
+        .filter(|(_, weight, total_staked)| !weight.is_zero() && !total_staked.is_zero())
+        .cloned()
+        .collect::<Vec<_>>();
+    let empty_pools = pools
+        .iter()
+        .filter(|(_, weight, total_staked)| !weight.is_zero() && total_staked.is_zero())
+        .map(|(denom, _, _)| denom.clone())
+        .collect::<Vec<String>>();
+
+    // when no pool has stakers there is nothing to redistribute to, so the rewards are queued
+    let applied_policy = match empty_pool_policy {
+        EmptyPoolPolicy::Redistribute if staked_pools.is_empty() => EmptyPoolPolicy::Queue,
+        policy => policy.clone(),
+    };
+    let recipients = match applied_policy {
+        EmptyPoolPolicy::Redistribute => staked_pools,
+        _ => pools,
+    };
+    let weights = recipients.iter().map(|(_, weight, _)| *weight).collect::<Vec<Uint64>>();
+
+    let mut returned = Coins::default();
+    for reward in rewards.iter() {
+        let shares = split_by_weight(reward.amount, &weights)?;
+
+        for ((denom, _, total_staked), share) in recipients.iter().zip(shares) {
+            if share.is_zero() {
+                continue;
+            }
+
+            if total_staked.is_zero() && applied_policy == EmptyPoolPolicy::ReturnToSender {
+                returned.add(coin(share.u128(), reward.denom.clone()))?;
+                continue;
+            }
+
+            let pool_state = pool_states
+                .get_mut(denom)
+                .ok_or_else(|| StdError::not_found(format!("pool state {}", denom)))?;
+            credit_pool(pool_state, &reward.denom, share, *total_staked)?;
+        }
+    }
+
+    for (denom, _, _) in &recipients {
+        if let Some(pool_state) = pool_states.get_mut(denom) {
+            pool_state.block_height = Uint64::from(block.height);
         }
+    }
+
+    Ok(PoolsCredit {
+        returned,
+        empty_pools,
+        applied_policy,
+    })
+}
 
-        let pool_state = POOL_STATE.load(deps.storage, &distro.denom.denom)?;
+/// Part of the schedule vested at `time`, including what was already emitted.
+fn vested_amount(schedule: &EmissionSchedule, time: Timestamp) -> Uint128 {
+    if time <= schedule.start {
+        return Uint128::zero();
+    }
+
+    let elapsed = time.min(schedule.end).seconds() - schedule.start.seconds();
+    let duration = schedule.end.seconds() - schedule.start.seconds();
+
+    schedule.total_amount.multiply_ratio(elapsed, duration)
+}
+
+/// Returns the pool states with every emission elapsed up to `block` applied and following
+/// the registered staking contracts, along with the schedules that emitted something and
+/// must be saved, or removed once finished, and whether any pool changed contract.
+fn pool_states_with_emissions(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    stake_change: Option<&StakeChange>,
+) -> Result<(PoolStates, EmissionSchedules, bool), ContractError> {
+    let block = &env.block;
+    let mut pool_states = load_pool_states(deps.storage)?;
+    let mut total_emitted = Coins::default();
+
+    // pools that never followed a contract credit the stakers of the one registered now
+    let mut followed_changed = false;
+    for pool_state in pool_states.values_mut().filter(|pool_state| !followed_any_contract(pool_state)) {
+        followed_changed |= follow_registered_contract(deps, env, config, pool_state)?;
+    }
+    let mut updated_schedules = vec![];
+
+    let schedules = EMISSION_SCHEDULES
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (id, mut schedule) in schedules {
+        let vested = vested_amount(&schedule, block.time);
+        if vested <= schedule.emitted {
+            continue;
+        }
 
-        let total_rewards = pool_state.total_rewards
-            .checked_add(denom_rewards)?;
+        total_emitted.add(coin(vested.checked_sub(schedule.emitted)?.u128(), schedule.denom.clone()))?;
+        schedule.emitted = vested;
+        updated_schedules.push((id, schedule));
+    }
 
-        let updated_pool_state = PoolState {
-            denom: distro.clone().denom,
-            total_rewards,
-            block_height: Uint64::from(env.block.height),
+    if !total_emitted.is_empty() {
+        // emissions have no sender to return rewards to, so those are queued instead
+        let empty_pool_policy = match config.empty_pool_policy {
+            EmptyPoolPolicy::ReturnToSender => EmptyPoolPolicy::Queue,
+            ref policy => policy.clone(),
         };
+        add_rewards_to_pools(deps, config, &mut pool_states, &total_emitted, block, &empty_pool_policy, stake_change)?;
+    }
 
-        POOL_STATE.save(deps.storage, &distro.denom.denom, &updated_pool_state, env.block.height)?;
+    // the emissions elapsed so far belong to the stakers of the contracts followed until now
+    followed_changed |= follow_registered_contracts(deps, env, config, &mut pool_states)?;
 
-        let stakers = query_staking_contract_by_denom(
-            &deps,
-            &distro.denom.denom,
-            config.staking_orchestrator_addr.to_string(),
-        )?;
+    Ok((pool_states, updated_schedules, followed_changed))
+}
 
-        for staker in stakers.stakers {
-            let mut user_state = USER_STATE.load(
-                deps.storage,
-                &Addr::unchecked(&staker.address)
-            ).unwrap_or(UserState {
-                reward_debt: Uint128::zero(),
-                last_claim_block_height: Uint64::zero(),
-                rewards_data: HashMap::new(),
-            });
-            let user_rewards = staker.balance
-                .checked_mul(denom_rewards)?
-                .checked_div(stakers.total_staked)?;
-
-            let values = user_state.rewards_data
-                .entry(distro.denom.denom.clone())
-                .or_insert_with(|| RewardsRecord { rewards: Uint128::zero() });
-            values.rewards = values.rewards.checked_add(user_rewards)?;
-
-            let updated_user_state = UserState {
-                reward_debt: user_state.reward_debt.checked_add(user_rewards)?,
-                last_claim_block_height: user_state.last_claim_block_height,
-                rewards_data: user_state.rewards_data,
-            };
-
-            USER_STATE.save(
-                deps.storage,
-                &Addr::unchecked(&staker.address),
-                &updated_user_state,
-                env.block.height
-            )?;
+/// Streams the elapsed emissions into the pools. Runs before any pool or user state is
+/// read so every action sees the rewards emitted up to the current block.
+fn process_emissions(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+) -> Result<PoolStates, ContractError> {
+    process_emissions_before(deps, env, config, None)
+}
+
+/// Streams the elapsed emissions into the pools over the stake held before `stake_change`,
+/// since they accrued before it.
+fn process_emissions_before(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    stake_change: Option<&StakeChange>,
+) -> Result<PoolStates, ContractError> {
+    let (pool_states, updated_schedules, followed_changed) =
+        pool_states_with_emissions(deps.as_ref(), env, config, stake_change)?;
+
+    for (id, schedule) in &updated_schedules {
+        // finished schedules owe nothing more, so they stop being loaded on every call
+        if schedule.emitted == schedule.total_amount {
+            EMISSION_SCHEDULES.remove(deps.storage, *id);
+        } else {
+            EMISSION_SCHEDULES.save(deps.storage, *id, schedule)?;
         }
     }
 
-    Ok(Response::new()
-        .add_attribute("action", "distribute_rewards")
-        .add_attribute("total_rewards_to_distribute", total_rewards_to_distribute)
-    )
+    if !updated_schedules.is_empty() || followed_changed {
+        save_pool_states(deps.storage, &pool_states, env.block.height)?;
+    }
+
+    Ok(pool_states)
 }
 
-const STAKERS_LIMIT: u32 = 1000u32;
+/// Snapshots at the current height hold the values from the start of the block, while the
+/// stake hooks checkpoint the balances held right before each change. Balances are read at
+/// the next height so both include the changes made earlier in the block.
+fn current_height(block: &BlockInfo) -> Option<u64> {
+    Some(block.height + 1)
+}
 
-struct StakingBag {
-    stakers: Vec<StakerBalanceResponse>,
-    total_staked: Uint128,
+/// Staking contract registered for `denom` in the orchestrator, none for a denom without one.
+fn registered_staking_contract(deps: Deps, config: &Config, denom: &str) -> StdResult<Option<Addr>> {
+    let registered = deps.querier
+        .query_wasm_smart::<symphony_interfaces::orchestrator::StakingContractByDenomResponse>(
+            &config.staking_orchestrator_addr,
+            &symphony_interfaces::orchestrator::QueryMsg::StakingContractByDenom {
+                denom: denom.to_string(),
+            },
+        );
+
+    match registered {
+        Ok(response) => Ok(Some(deps.api.addr_validate(&response.registered_contract.address)?)),
+        // the lookup only fails for unregistered denoms if the orchestrator answers otherwise
+        Err(error) => {
+            deps.querier
+                .query_wasm_smart::<symphony_interfaces::orchestrator::RetiredStakingContractsResponse>(
+                    &config.staking_orchestrator_addr,
+                    &symphony_interfaces::orchestrator::QueryMsg::RetiredStakingContracts {
+                        denom: denom.to_string(),
+                    },
+                )
+                .map_err(|_| error)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Tokens per staked unit left by the last slash of `staking_contract`. Contracts without
+/// slash events were never slashed.
+fn query_exchange_rate(deps: Deps, staking_contract: &Addr) -> Decimal {
+    deps.querier
+        .query_wasm_smart::<symphony_interfaces::staking::SlashEventsResponse>(
+            staking_contract,
+            &symphony_interfaces::staking::QueryMsg::SlashEvents {
+                start_height: None,
+                end_height: None,
+            },
+        )
+        .ok()
+        .and_then(|response| response.slash_events.last().map(|event| event.exchange_rate))
+        .unwrap_or(Decimal::one())
+}
+
+/// Current accumulator of every reward denom of the pool.
+fn accumulators(pool_state: &PoolState) -> BTreeMap<String, Decimal> {
+    pool_state.rewards
+        .iter()
+        .map(|(reward_denom, pool_rewards)| (reward_denom.clone(), pool_rewards.reward_per_token))
+        .collect()
 }
 
-fn query_staking_contract_by_denom(
-    deps: &DepsMut,
-    denom: &str,
-    orchestrator_addr: String
-) -> Result<StakingBag, ContractError> {
-    let mut start_after = None;
-    let limit = Some(STAKERS_LIMIT);
-    let mut stakers_acc: Vec<StakerBalanceResponse> = vec![];
+/// Whether the pool followed any staking contract so far.
+fn followed_any_contract(pool_state: &PoolState) -> bool {
+    pool_state.staking_contract.is_some() || !pool_state.retired_contracts.is_empty()
+}
 
-    let query_request = &symphony_interfaces::orchestrator::QueryMsg::ListStakersByDenom {
-        denom: denom.to_string(),
-        start_after,
-        limit,
+/// Moves the pool to the staking contract registered for its denom, as long as it notifies
+/// this contract of the stake changes. The contract followed
+/// before is retired with the accumulators reached so far, so its stakers keep what they
+/// accrued in it while the stakers of the new one only accrue from now on.
+/// Returns whether the pool changed contract.
+fn follow_registered_contract(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    pool_state: &mut PoolState,
+) -> StdResult<bool> {
+    let registered = match registered_staking_contract(deps, config, &pool_state.denom.denom)? {
+        // without the hook its stakers could not be checkpointed, so nothing accrues for them
+        Some(staking_contract) if !is_hooked(deps, env, &staking_contract)? => None,
+        registered => registered,
     };
-    let response: symphony_interfaces::orchestrator::ListStakersByDenomResponse = deps.querier
-        .query_wasm_smart(orchestrator_addr.clone(), query_request)?;
-    // add all response stakers to stakers_acc
-    stakers_acc.extend(response.stakers);
-
-    loop {
-        let last_staker = stakers_acc.last();
-        match last_staker {
-            None => return Ok(StakingBag {
-                stakers: vec![],
-                total_staked: Uint128::zero(),
-            }),
-            Some(last_staker) => {
-                start_after = Some(last_staker.address.clone());
-                let query_request = &symphony_interfaces::orchestrator::QueryMsg::ListStakersByDenom {
-                    denom: denom.to_string(),
-                    start_after,
-                    limit,
-                };
-                let response: symphony_interfaces::orchestrator::ListStakersByDenomResponse = deps.querier
-                    .query_wasm_smart(orchestrator_addr.clone(), query_request)?;
-                if response.stakers.is_empty() {
-                    return Ok(StakingBag {
-                        stakers: stakers_acc.clone(),
-                        total_staked: stakers_acc
-                            .clone()
-                            .iter()
-                            .fold(Uint128::zero(), |acc, x| acc + x.balance),
-                    });
-                }
-                stakers_acc.extend(response.stakers);
-            }
+    if registered == pool_state.staking_contract {
+        return Ok(false);
+    }
+
+    // the first contract of a pool holds the records kept before pools followed contracts
+    let followed_before = followed_any_contract(pool_state);
+    let accumulators = accumulators(pool_state);
+
+    if let Some(address) = pool_state.staking_contract.take() {
+        pool_state.retired_contracts.push(RetiredStakingContract {
+            address,
+            exchange_rate: pool_state.exchange_rate,
+            followed_from: std::mem::take(&mut pool_state.followed_from),
+            followed_until: accumulators.clone(),
+        });
+    }
+
+    if let Some(staking_contract) = &registered {
+        if followed_before {
+            pool_state.exchange_rate = query_exchange_rate(deps, staking_contract);
+            pool_state.followed_from = accumulators;
         }
     }
+    pool_state.staking_contract = registered;
+
+    Ok(true)
 }
 
-fn execute_claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    assert_not_paused(deps.storage)?;
-    let config = CONFIG.load(deps.storage)?;
+/// Moves every pool to the staking contract registered for its denom.
+/// Returns whether any pool changed contract.
+fn follow_registered_contracts(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    pool_states: &mut PoolStates,
+) -> StdResult<bool> {
+    let mut changed = false;
+    for pool_state in pool_states.values_mut() {
+        changed |= follow_registered_contract(deps, env, config, pool_state)?;
+    }
+    Ok(changed)
+}
+
+/// Whether `staking_contract` notifies this contract of every stake change, which
+/// checkpoints each staker before their balance changes.
+fn is_hooked(deps: Deps, env: &Env, staking_contract: &Addr) -> StdResult<bool> {
+    let hooks: HooksResponse = deps.querier.query_wasm_smart(
+        staking_contract,
+        &symphony_interfaces::staking::QueryMsg::Hooks {},
+    )?;
+    Ok(hooks.hooks.contains(&env.contract.address.to_string()))
+}
+
+/// Whether the pool follows `staking_contract` or followed it before.
+fn is_followed_contract(pool_state: &PoolState, staking_contract: &Addr) -> bool {
+    pool_state.staking_contract.as_ref() == Some(staking_contract)
+        || pool_state.retired_contracts.iter().any(|retired| retired.address == *staking_contract)
+}
+
+fn query_total_staked(deps: Deps, staking_contract: &Addr, block: &BlockInfo) -> StdResult<Uint128> {
+    let total: symphony_interfaces::staking::TotalStakedAtHeightResponse = deps.querier
+        .query_wasm_smart(
+            staking_contract,
+            &symphony_interfaces::staking::QueryMsg::TotalStakedAtHeight { height: current_height(block) },
+        )?;
+
+    Ok(total.total)
+}
+
+/// Balance staked by `address` in `staking_contract`. A stake hook passes the balance held
+/// before the change it reports as `known`, since the current one already includes it.
+fn query_staked_balance(
+    deps: Deps,
+    staking_contract: &Addr,
+    address: &Addr,
+    known: Option<(&Addr, Uint128)>,
+    block: &BlockInfo,
+) -> StdResult<Uint128> {
+    if let Some((contract, balance)) = known {
+        if contract == staking_contract {
+            return Ok(balance);
+        }
+    }
+
+    let response: symphony_interfaces::staking::StakedBalanceAtHeightResponse = deps.querier
+        .query_wasm_smart(
+            staking_contract,
+            &symphony_interfaces::staking::QueryMsg::StakedBalanceAtHeight {
+                address: address.to_string(),
+                height: current_height(block),
+            },
+        )?;
+
+    Ok(response.balance)
+}
+
+/// Accumulator growth of a reward denom between `from` and `until` not settled in `records`.
+fn unsettled_growth(
+    records: &BTreeMap<String, RewardsRecord>,
+    reward_denom: &str,
+    from: &BTreeMap<String, Decimal>,
+    until: &BTreeMap<String, Decimal>,
+) -> StdResult<Decimal> {
+    let paid = records.get(reward_denom).map(|record| record.reward_per_token_paid).unwrap_or_default();
+    let start = paid.max(from.get(reward_denom).copied().unwrap_or_default());
+    let until = until.get(reward_denom).copied().unwrap_or_default();
+
+    if until <= start {
+        return Ok(Decimal::zero());
+    }
+    Ok(until.checked_sub(start)?)
+}
+
+/// Settles every reward denom of a pool for a user, over the contract the pool follows and
+/// the ones it followed before, and moves the checkpoints to the current accumulators.
+/// Balances in a retired contract that stopped notifying this one may have changed unseen,
+/// so what is left to settle there is forfeited.
+///
+/// Every window rounds down per reward denom, so each settlement leaves the user less than
+/// one token per window and reward denom short. That dust is never claimable and not part of
+/// the remainder, but it stays in `total_rewards`, so it is still counted as owed.
+fn settle_user_pool(
+    deps: Deps,
+    env: &Env,
+    pool_state: &PoolState,
+    user_state: &mut UserState,
+    address: &Addr,
+    known: Option<(&Addr, Uint128)>,
+) -> Result<(), ContractError> {
+    let records = user_state.rewards_data
+        .entry(pool_state.denom.denom.clone())
+        .or_default();
+
+    // the followed contract was checked for the hook when the pool states were loaded
+    let mut windows = pool_state.retired_contracts
+        .iter()
+        .map(|retired| (&retired.address, retired.exchange_rate, &retired.followed_from, retired.followed_until.clone(), true))
+        .collect::<Vec<_>>();
+    if let Some(staking_contract) = &pool_state.staking_contract {
+        windows.push((staking_contract, pool_state.exchange_rate, &pool_state.followed_from, accumulators(pool_state), false));
+    }
+
+    let mut accrued = BTreeMap::<String, Uint128>::new();
+    for (staking_contract, exchange_rate, from, until, retired) in windows {
+        let growth = pool_state.rewards
+            .keys()
+            .map(|reward_denom| Ok((reward_denom, unsettled_growth(records, reward_denom, from, &until)?)))
+            .collect::<StdResult<Vec<_>>>()?;
+        // contracts whose window is settled are not queried
+        if growth.iter().all(|(_, growth)| growth.is_zero()) {
+            continue;
+        }
+
+        let notifying = known.is_some_and(|(sender, _)| sender == staking_contract);
+        if retired && !notifying && !is_hooked(deps, env, staking_contract)? {
+            continue;
+        }
+
+        let staked = query_staked_balance(deps, staking_contract, address, known, &env.block)?;
+        let units = staked_units(staked, exchange_rate)?;
+        for (reward_denom, growth) in growth {
+            let amount = accrued.entry(reward_denom.clone()).or_default();
+            *amount = amount.checked_add(units.checked_mul_floor(growth)?)?;
+        }
+    }
+
+    for (reward_denom, pool_rewards) in &pool_state.rewards {
+        let amount = accrued.get(reward_denom).copied().unwrap_or_default();
+
+        let record = records.entry(reward_denom.clone()).or_default();
+        record.rewards = record.rewards.checked_add(amount)?;
+        record.reward_per_token_paid = pool_rewards.reward_per_token;
+
+        let debt = user_state.reward_debt.entry(reward_denom.clone()).or_default();
+        *debt = debt.checked_add(amount)?;
+    }
+
+    Ok(())
+}
+
+/// Settles every pool for a user at their current staked balances.
+fn settle_user_state(
+    deps: Deps,
+    env: &Env,
+    pool_states: &PoolStates,
+    user_state: &mut UserState,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    for pool_state in pool_states.values() {
+        settle_user_pool(deps, env, pool_state, user_state, address, None)?;
+    }
+
+    Ok(())
+}
+
+/// Non-zero reward debt as coins, sorted by denom.
+fn reward_debt_coins(user_state: &UserState) -> Vec<Coin> {
+    user_state.reward_debt
+        .iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(denom, amount)| coin(amount.u128(), denom))
+        .collect()
+}
+
+fn load_settled_user_state(
+    deps: Deps,
+    env: &Env,
+    pool_states: &PoolStates,
+    address: &Addr,
+) -> Result<UserState, ContractError> {
+    let mut user_state = USER_STATE.may_load(deps.storage, address)?.unwrap_or_default();
+    settle_user_state(deps, env, pool_states, &mut user_state, address)?;
+
+    Ok(user_state)
+}
+
+/// Settled state of a user and the rewards they would receive by claiming now.
+struct PendingClaim {
+    user_state: UserState,
+    rewards_by_pool: Vec<(String, Vec<Coin>)>,
+    total_rewards: Vec<Coin>,
+}
 
-    let user_state = USER_STATE.load(deps.storage, &info.sender)?;
+fn pending_claim(
+    deps: Deps,
+    env: &Env,
+    pool_states: &PoolStates,
+    address: &Addr,
+) -> Result<PendingClaim, ContractError> {
+    let user_state = load_settled_user_state(deps, env, pool_states, address)?;
+
+    let rewards_by_pool = user_state.rewards_data
+        .iter()
+        .map(|(denom, records)| {
+            let rewards = records
+                .iter()
+                .filter(|(_, record)| !record.rewards.is_zero())
+                .map(|(reward_denom, record)| coin(record.rewards.u128(), reward_denom))
+                .collect::<Vec<Coin>>();
+            (denom.clone(), rewards)
+        })
+        .filter(|(_, rewards)| !rewards.is_empty())
+        .collect();
+
+    // every reward denom is sent to the user at once
+    let total_rewards = reward_debt_coins(&user_state);
+
+    Ok(PendingClaim {
+        user_state,
+        rewards_by_pool,
+        total_rewards,
+    })
+}
 
-    // send rewards to user
-    let total_rewards = user_state.reward_debt;
-    let rewards_data = user_state.rewards_data;
-    let rev_denom = config.reward_token.denom.clone();
+/// Checks that a pending claim can be paid right now without leaving the contract unable
+/// to pay everyone else.
+fn validate_claim(
+    deps: Deps,
+    env: &Env,
+    pool_states: &PoolStates,
+    claim: &PendingClaim,
+) -> Result<(), ContractError> {
+    assert_not_paused(deps.storage)?;
 
-    if total_rewards.is_zero() {
+    if claim.total_rewards.is_empty() {
         return Err(ContractError::NoRewardsToClaim {});
     }
 
+    for reward in &claim.total_rewards {
+        let balance = deps.querier.query_balance(&env.contract.address, &reward.denom)?;
+        if balance.amount < reward.amount {
+            return Err(ContractError::InsufficientRewardsBalance { denom: reward.denom.clone() });
+        }
+    }
+
+    let denoms = claim.total_rewards.iter().map(|reward| reward.denom.clone()).collect::<Vec<String>>();
+    assert_solvent(deps, env, pool_states, &denoms, &Coins::default())
+}
+
+/// Rewards the contract owes per denom: everything added to the pools and not claimed yet,
+/// plus the emissions not vested yet, which are either emitted later or refunded.
+fn reward_liabilities(deps: Deps, env: &Env, pool_states: &PoolStates) -> StdResult<Coins> {
+    let mut liabilities = Coins::default();
+
+    for pool_state in pool_states.values() {
+        for (reward_denom, pool_rewards) in &pool_state.rewards {
+            liabilities.add(coin(pool_rewards.total_rewards.u128(), reward_denom))?;
+        }
+    }
+
+    let schedules = EMISSION_SCHEDULES
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (_, schedule) in schedules {
+        let emitted = vested_amount(&schedule, env.block.time).max(schedule.emitted);
+        liabilities.add(coin(schedule.total_amount.checked_sub(emitted)?.u128(), schedule.denom))?;
+    }
+
+    Ok(liabilities)
+}
+
+/// Fails if the liabilities in any of `denoms` exceed the contract balance once `outgoing`
+/// funds that are not liabilities have left it.
+fn assert_solvent(
+    deps: Deps,
+    env: &Env,
+    pool_states: &PoolStates,
+    denoms: &[String],
+    outgoing: &Coins,
+) -> Result<(), ContractError> {
+    let liabilities = reward_liabilities(deps, env, pool_states)?;
+
+    for denom in denoms {
+        let balance = deps.querier.query_balance(&env.contract.address, denom)?;
+        let available = balance.amount.saturating_sub(outgoing.amount_of(denom));
+        if liabilities.amount_of(denom) > available {
+            return Err(ContractError::Insolvent { denom: denom.clone() });
+        }
+    }
+
+    Ok(())
+}
+
+fn execute_claim_rewards(mut deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut pool_states = process_emissions(deps.branch(), &env, &config)?;
+    let claim = pending_claim(deps.as_ref(), &env, &pool_states, &info.sender)?;
+    validate_claim(deps.as_ref(), &env, &pool_states, &claim)?;
+
+    let PendingClaim { user_state, total_rewards, .. } = claim;
+
     let rewards_msg = cosmwasm_std::CosmosMsg::Bank(cosmwasm_std::BankMsg::Send {
         to_address: info.sender.to_string(),
-        amount: vec![cosmwasm_std::Coin {
-            denom: rev_denom,
-            amount: total_rewards,
-        }],
+        amount: total_rewards.clone(),
     });
 
-    for reward in rewards_data {
-        let denom = reward.0;
-        let record = reward.1;
-        let pool_state = POOL_STATE.load(deps.storage, &denom)?;
+    let mut rewards_data = user_state.rewards_data;
+    for (denom, records) in rewards_data.iter_mut() {
+        if records.values().all(|record| record.rewards.is_zero()) {
+            continue;
+        }
 
-        let updated_total_rewards = pool_state.total_rewards.checked_sub(record.rewards)?;
-        let updated_pool_state = PoolState {
-            denom: pool_state.denom,
-            total_rewards: updated_total_rewards,
-            block_height: Uint64::from(env.block.height),
-        };
+        let pool_state = pool_states
+            .get_mut(denom)
+            .ok_or_else(|| StdError::not_found(format!("pool state {}", denom)))?;
+
+        for (reward_denom, record) in records.iter_mut() {
+            if record.rewards.is_zero() {
+                continue;
+            }
+
+            let pool_rewards = pool_state.rewards.entry(reward_denom.clone()).or_default();
+            pool_rewards.total_rewards = pool_rewards.total_rewards.checked_sub(record.rewards)?;
+            record.rewards = Uint128::zero();
+        }
 
-        POOL_STATE.save(deps.storage, &denom, &updated_pool_state, env.block.height)?;
+        pool_state.block_height = Uint64::from(env.block.height);
+        POOL_STATE.save(deps.storage, denom, pool_state, env.block.height)?;
     }
 
     let updated_user_state = UserState {
-        reward_debt: Uint128::zero(),
+        reward_debt: BTreeMap::new(),
         last_claim_block_height: Uint64::from(env.block.height),
-        rewards_data: HashMap::new(),
+        rewards_data,
     };
 
     USER_STATE.save(deps.storage, &info.sender, &updated_user_state, env.block.height)?;
 
     Ok(Response::new()
         .add_attribute("action", "claim_rewards")
-        .add_attribute("total_rewards", total_rewards)
+        .add_attribute("total_rewards", Coins::try_from(total_rewards).map_err(StdError::from)?.to_string())
         .add_message(rewards_msg)
     )
 }
 
+/// Checkpoints the user's rewards in the pool with the balance held before the change,
+/// so the new balance only accrues rewards distributed from now on. Contracts the pool
+/// followed before keep notifying, so their stakers settle what they accrued there.
+fn execute_stake_changed_hook(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    hook: StakeChangedHookMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let response = Response::new()
+        .add_attribute("action", "stake_changed_hook")
+        .add_attribute("address", hook.addr.clone())
+        .add_attribute("denom", hook.denom.clone());
+
+    let stake_change = StakeChange { staking_contract: &info.sender, hook: &hook };
+    let pool_states = process_emissions_before(deps.branch(), &env, &config, Some(&stake_change))?;
+    let pool_state = match pool_states.get(&hook.denom) {
+        // denom is not rewarded, nothing to checkpoint
+        None => return Ok(response),
+        Some(pool_state) => pool_state,
+    };
+    if !is_followed_contract(pool_state, &info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let user_addr = deps.api.addr_validate(&hook.addr)?;
+    let mut user_state = USER_STATE.may_load(deps.storage, &user_addr)?.unwrap_or_default();
+    settle_user_pool(
+        deps.as_ref(),
+        &env,
+        pool_state,
+        &mut user_state,
+        &user_addr,
+        Some((&info.sender, hook.old_balance)),
+    )?;
+
+    USER_STATE.save(deps.storage, &user_addr, &user_state, env.block.height)?;
+
+    Ok(response)
+}
+
+/// Moves the pool to the exchange rate left by a slash. Balances shrink with the rate while
+/// staked units do not, so the rewards accrued before the slash stay whole.
+fn execute_slash_hook(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    hook: SlashHookMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if hook.exchange_rate.is_zero() {
+        return Err(ContractError::InvalidExchangeRate {});
+    }
+
+    let response = Response::new()
+        .add_attribute("action", "slash_hook")
+        .add_attribute("denom", hook.denom.clone())
+        .add_attribute("exchange_rate", hook.exchange_rate.to_string());
+
+    // balances are already slashed, so the rate must be in place before emissions are credited
+    let mut pool_state = match POOL_STATE.may_load(deps.storage, &hook.denom)? {
+        // denom is not rewarded, nothing to rescale
+        None => return Ok(response),
+        Some(pool_state) => pool_state,
+    };
+
+    // a contract registered since the pool was last updated is only followed from now on
+    if !is_followed_contract(&pool_state, &info.sender) {
+        pool_state = process_emissions(deps.branch(), &env, &config)?
+            .remove(&hook.denom)
+            .ok_or_else(|| StdError::not_found(format!("pool state {}", hook.denom)))?;
+    }
+
+    let mut followed = false;
+    if pool_state.staking_contract.as_ref() == Some(&info.sender) {
+        pool_state.exchange_rate = hook.exchange_rate;
+        followed = true;
+    }
+    for retired in pool_state.retired_contracts.iter_mut() {
+        if retired.address == info.sender {
+            retired.exchange_rate = hook.exchange_rate;
+            followed = true;
+        }
+    }
+    if !followed {
+        return Err(ContractError::Unauthorized {});
+    }
+    POOL_STATE.save(deps.storage, &hook.denom, &pool_state, env.block.height)?;
+
+    process_emissions(deps.branch(), &env, &config)?;
+
+    Ok(response)
+}
+
+fn execute_create_emission_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start: Timestamp,
+    end: Timestamp,
+    total_amount: Uint128,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    if start < env.block.time || end <= start {
+        return Err(ContractError::InvalidEmissionSchedule {});
+    }
+
+    let funds = cw_utils::one_coin(&info)
+        .map_err(|_| ContractError::InvalidEmissionFunds {})?;
+    if !config.reward_tokens.iter().any(|token| token.denom == funds.denom) {
+        return Err(ContractError::InvalidRewardDenom { denom: funds.denom });
+    }
+    if funds.amount != total_amount {
+        return Err(ContractError::InvalidEmissionFunds {});
+    }
+
+    let id = EMISSION_SCHEDULE_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    EMISSION_SCHEDULE_COUNT.save(deps.storage, &id)?;
+
+    let schedule = EmissionSchedule {
+        funder: info.sender,
+        denom: funds.denom.clone(),
+        start,
+        end,
+        total_amount,
+        emitted: Uint128::zero(),
+    };
+    EMISSION_SCHEDULES.save(deps.storage, id, &schedule)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_emission_schedule")
+        .add_attribute("id", id.to_string())
+        .add_attribute("denom", funds.denom)
+        .add_attribute("start", start.to_string())
+        .add_attribute("end", end.to_string())
+        .add_attribute("total_amount", total_amount)
+    )
+}
+
+fn execute_cancel_emission_schedule(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    // emit everything vested so far before removing the schedule
+    process_emissions(deps.branch(), &env, &config)?;
+
+    let schedule = EMISSION_SCHEDULES.load(deps.storage, id)?;
+    EMISSION_SCHEDULES.remove(deps.storage, id);
+
+    let refund = schedule.total_amount.checked_sub(schedule.emitted)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "cancel_emission_schedule")
+        .add_attribute("id", id.to_string())
+        .add_attribute("refund", refund);
+
+    if !refund.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: schedule.funder.to_string(),
+            amount: vec![coin(refund.u128(), schedule.denom)],
+        });
+    }
+
+    Ok(response)
+}
+
 fn execute_pause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
     cw_ownable::assert_owner(deps.storage, &info.sender)?;
 
@@ -369,15 +1290,31 @@ fn execute_unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, Contrac
 // }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from_version = migrate_contract(deps.storage, &env, CONTRACT_NAME, CONTRACT_VERSION, MIGRATIONS)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", from_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
         QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
-        QueryMsg::AllPoolStates {} => to_json_binary(&query_all_pool_states(deps)?),
-        QueryMsg::PoolState { denom, block_height } => to_json_binary(&query_pool_state(deps, denom, block_height)?),
+        QueryMsg::AllPoolStates {} => to_json_binary(&query_all_pool_states(deps, env)?),
+        QueryMsg::PoolState { denom, block_height } => to_json_binary(&query_pool_state(deps, env, denom, block_height)?),
         QueryMsg::AllUserStates {} => to_json_binary(&query_all_user_states(deps)?),
-        QueryMsg::UserState { address, block_height } => to_json_binary(&query_user_state(deps, address, block_height)?),
+        QueryMsg::UserState { address, block_height } => to_json_binary(&query_user_state(deps, env, address, block_height)?),
         QueryMsg::IsPaused {} => to_json_binary(&query_is_paused(deps)?),
+        QueryMsg::EmissionSchedule { id } => to_json_binary(&query_emission_schedule(deps, env, id)?),
+        QueryMsg::AllEmissionSchedules {} => to_json_binary(&query_all_emission_schedules(deps, env)?),
+        QueryMsg::Remainders {} => to_json_binary(&query_remainders(deps, env)?),
+        QueryMsg::SimulateClaim { address } => to_json_binary(&query_simulate_claim(deps, env, address)?),
+        QueryMsg::Solvency {} => to_json_binary(&query_solvency(deps, env)?),
+        QueryMsg::IsRewarded { denom } => to_json_binary(&query_is_rewarded(deps, denom)?),
     }
 }
 
@@ -386,25 +1323,29 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
     Ok(ConfigResponse {
         staking_orchestrator_addr: config.staking_orchestrator_addr.to_string(),
-        reward_token: config.reward_token.clone(),
+        reward_tokens: config.reward_tokens.clone(),
         rewards_distribution: config.rewards_distribution.clone(),
+        empty_pool_policy: config.empty_pool_policy,
     })
 }
 
-fn query_all_pool_states(deps: Deps) -> StdResult<ListPoolStatesResponse> {
-    let pool_states: Vec<PoolState> = POOL_STATE
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .map(|item| {
-            let (_, pool_state) = item?;
-            Ok(pool_state)
-        })
-        .collect::<StdResult<Vec<PoolState>>>()?;
+/// Current pool states, including the emissions elapsed since they were last saved.
+fn query_current_pool_states(deps: Deps, env: &Env) -> StdResult<PoolStates> {
+    let config = CONFIG.load(deps.storage)?;
+    let (pool_states, _, _) = pool_states_with_emissions(deps, env, &config, None)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    Ok(pool_states)
+}
 
-    let states = pool_states.iter().map(|pool_state| {
+fn query_all_pool_states(deps: Deps, env: Env) -> StdResult<ListPoolStatesResponse> {
+    let pool_states = query_current_pool_states(deps, &env)?;
+
+    let states = pool_states.values().map(|pool_state| {
         PoolStateResponse {
             denom: pool_state.denom.clone(),
-            total_rewards: pool_state.total_rewards,
+            rewards: pool_state.rewards.clone(),
             block_height: pool_state.block_height,
+            active: pool_state.active,
         }
     }).collect();
 
@@ -415,9 +1356,13 @@ fn query_all_pool_states(deps: Deps) -> StdResult<ListPoolStatesResponse> {
     Ok(pool_states)
 }
 
-fn query_pool_state(deps: Deps, denom: String, block_height: Option<Uint64>) -> StdResult<PoolStateResponse> {
+fn query_pool_state(deps: Deps, env: Env, denom: String, block_height: Option<Uint64>) -> StdResult<PoolStateResponse> {
     let pool_state = match block_height {
-        None => Some(POOL_STATE.load(deps.storage, &denom)?),
+        None => Some(
+            query_current_pool_states(deps, &env)?
+                .remove(&denom)
+                .ok_or_else(|| StdError::not_found(format!("pool state {}", denom)))?
+        ),
         Some(height) => POOL_STATE.may_load_at_height(deps.storage, &denom, height.u64())?,
     };
 
@@ -425,8 +1370,9 @@ fn query_pool_state(deps: Deps, denom: String, block_height: Option<Uint64>) ->
         None => Err(StdError::not_found(denom)),
         Some(pool_state) => Ok(PoolStateResponse {
             denom: pool_state.denom.clone(),
-            total_rewards: pool_state.total_rewards,
+            rewards: pool_state.rewards.clone(),
             block_height: pool_state.block_height,
+            active: pool_state.active,
         }),
     }
 }
@@ -440,7 +1386,7 @@ fn query_all_user_states(
             let (address, user_state) = item?;
             Ok(UserStateResponse {
                 address: address.to_string(),
-                reward_debt: user_state.reward_debt,
+                reward_debt: reward_debt_coins(&user_state),
                 rewards_data: user_state.rewards_data,
             })
         })
@@ -453,9 +1399,17 @@ fn query_all_user_states(
     Ok(user_states)
 }
 
-fn query_user_state(deps: Deps, address: String, block_height: Option<Uint64>) -> StdResult<UserStateResponse> {
+fn query_user_state(deps: Deps, env: Env, address: String, block_height: Option<Uint64>) -> StdResult<UserStateResponse> {
     let user_state = match block_height {
-        None => Some(USER_STATE.load(deps.storage, &Addr::unchecked(&address))?),
+        None => {
+            // include the rewards accrued since the last checkpoint
+            let pool_states = query_current_pool_states(deps, &env)?;
+            let user_addr = deps.api.addr_validate(&address)?;
+            Some(
+                load_settled_user_state(deps, &env, &pool_states, &user_addr)
+                    .map_err(|e| StdError::generic_err(e.to_string()))?
+            )
+        },
         Some(height) => USER_STATE.may_load_at_height(deps.storage, &Addr::unchecked(&address), height.u64())?,
     };
 
@@ -463,7 +1417,7 @@ fn query_user_state(deps: Deps, address: String, block_height: Option<Uint64>) -
         None => Err(StdError::not_found(address)),
         Some(user_state) => Ok(UserStateResponse {
             address,
-            reward_debt: user_state.reward_debt,
+            reward_debt: reward_debt_coins(&user_state),
             rewards_data: user_state.rewards_data,
         })
     }
@@ -472,4 +1426,113 @@ fn query_user_state(deps: Deps, address: String, block_height: Option<Uint64>) -
 fn query_is_paused(deps: Deps) -> StdResult<IsPausedResponse> {
     let paused = PAUSED.load(deps.storage)?;
     Ok(IsPausedResponse { paused })
-}
\ No newline at end of file
+}
+
+fn query_is_rewarded(deps: Deps, denom: String) -> StdResult<IsRewardedResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(IsRewardedResponse {
+        rewarded: config.rewards_distribution.iter().any(|distro| distro.denom.denom == denom),
+    })
+}
+
+fn query_simulate_claim(deps: Deps, env: Env, address: String) -> StdResult<SimulateClaimResponse> {
+    let pool_states = query_current_pool_states(deps, &env)?;
+    let user_addr = deps.api.addr_validate(&address)?;
+
+    let claim = pending_claim(deps, &env, &pool_states, &user_addr)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let error = validate_claim(deps, &env, &pool_states, &claim).err();
+
+    Ok(SimulateClaimResponse {
+        address,
+        rewards_by_pool: claim.rewards_by_pool
+            .into_iter()
+            .map(|(denom, rewards)| PoolClaim { denom, rewards })
+            .collect(),
+        total_rewards: claim.total_rewards,
+        can_claim: error.is_none(),
+        reason: error.map(|e| e.to_string()),
+    })
+}
+
+fn query_solvency(deps: Deps, env: Env) -> StdResult<SolvencyResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let pool_states = query_current_pool_states(deps, &env)?;
+    let liabilities = reward_liabilities(deps, &env, &pool_states)?;
+
+    // include denoms no longer whitelisted that are still owed
+    let mut denoms = config.reward_tokens
+        .iter()
+        .map(|token| token.denom.clone())
+        .collect::<Vec<String>>();
+    for denom in liabilities.denoms() {
+        if !denoms.contains(&denom) {
+            denoms.push(denom);
+        }
+    }
+
+    let solvency = denoms
+        .into_iter()
+        .map(|denom| {
+            let balance = deps.querier.query_balance(&env.contract.address, &denom)?.amount;
+            let liabilities = liabilities.amount_of(&denom);
+            Ok(DenomSolvency {
+                denom,
+                liabilities,
+                balance,
+                surplus: balance.saturating_sub(liabilities),
+                deficit: liabilities.saturating_sub(balance),
+            })
+        })
+        .collect::<StdResult<Vec<DenomSolvency>>>()?;
+
+    Ok(SolvencyResponse { solvency })
+}
+
+fn query_remainders(deps: Deps, env: Env) -> StdResult<RemaindersResponse> {
+    let pool_states = query_current_pool_states(deps, &env)?;
+
+    let remainders = pool_states
+        .into_iter()
+        .map(|(denom, pool_state)| PoolRemainders {
+            denom,
+            remainders: pool_state.rewards
+                .into_iter()
+                .filter(|(_, pool_rewards)| !pool_rewards.remainder.is_zero())
+                .map(|(reward_denom, pool_rewards)| coin(pool_rewards.remainder.u128(), reward_denom))
+                .collect(),
+        })
+        .collect();
+
+    Ok(RemaindersResponse { remainders })
+}
+
+fn emission_schedule_response(id: u64, schedule: EmissionSchedule, env: &Env) -> EmissionScheduleResponse {
+    EmissionScheduleResponse {
+        id,
+        funder: schedule.funder.to_string(),
+        denom: schedule.denom.clone(),
+        start: schedule.start,
+        end: schedule.end,
+        total_amount: schedule.total_amount,
+        emitted: vested_amount(&schedule, env.block.time).max(schedule.emitted),
+    }
+}
+
+fn query_emission_schedule(deps: Deps, env: Env, id: u64) -> StdResult<EmissionScheduleResponse> {
+    let schedule = EMISSION_SCHEDULES.load(deps.storage, id)?;
+    Ok(emission_schedule_response(id, schedule, &env))
+}
+
+fn query_all_emission_schedules(deps: Deps, env: Env) -> StdResult<ListEmissionSchedulesResponse> {
+    let emission_schedules = EMISSION_SCHEDULES
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| {
+            let (id, schedule) = item?;
+            Ok(emission_schedule_response(id, schedule, &env))
+        })
+        .collect::<StdResult<Vec<EmissionScheduleResponse>>>()?;
+
+    Ok(ListEmissionSchedulesResponse { emission_schedules })
+}
@@ -1,4 +1,5 @@
-use cosmwasm_std::{DivideByZeroError, OverflowError, StdError};
+use cosmwasm_std::{CheckedFromRatioError, CheckedMultiplyFractionError, DivideByZeroError, OverflowError, StdError};
+use symphony_utils::migrate::MigrationError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +7,9 @@ pub enum ContractError {
     #[error(transparent)]
     Std(#[from] StdError),
 
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+
     #[error(transparent)]
     Ownership(#[from] cw_ownable::OwnershipError),
 
@@ -17,16 +21,61 @@ pub enum ContractError {
     #[error("Invalid rewards amount, should be greater than zero")]
     NoRewardsToDistribute,
 
+    #[error("Denom {denom} is not a whitelisted reward token")]
+    InvalidRewardDenom {
+        denom: String,
+    },
+
+    #[error("Denom {denom} has no staking contract registered in the orchestrator")]
+    UnregisteredPoolDenom {
+        denom: String,
+    },
+
+    #[error("The staking contract of {denom} does not notify this contract of stake changes")]
+    UnhookedPoolDenom {
+        denom: String,
+    },
+
+    #[error("At least one reward token must be whitelisted")]
+    NoRewardTokens,
+
     #[error(transparent)]
     OverflowError(#[from] OverflowError),
 
     #[error(transparent)]
     DivideByZeroError(#[from] DivideByZeroError),
 
+    #[error(transparent)]
+    CheckedFromRatioError(#[from] CheckedFromRatioError),
+
+    #[error(transparent)]
+    CheckedMultiplyFractionError(#[from] CheckedMultiplyFractionError),
+
     #[error("There are no rewards to claim")]
     NoRewardsToClaim,
 
+    #[error("Contract balance of {denom} is too low to pay the rewards")]
+    InsufficientRewardsBalance {
+        denom: String,
+    },
+
+    #[error("Rewards owed in {denom} exceed the contract balance")]
+    Insolvent {
+        denom: String,
+    },
+
     #[error("Cannot execute this action while the contract is paused")]
     ContractPaused,
 
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Emission schedule must end after it starts and cannot start in the past")]
+    InvalidEmissionSchedule,
+
+    #[error("Emission schedule must be funded with exactly the total amount of reward token")]
+    InvalidEmissionFunds,
+
+    #[error("Exchange rate of a slashed pool must be greater than zero")]
+    InvalidExchangeRate,
 }
\ No newline at end of file
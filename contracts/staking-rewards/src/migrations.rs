@@ -0,0 +1,155 @@
+use crate::error::ContractError;
+use crate::state::{Config, EmptyPoolPolicy, PoolRewards, PoolState, RewardsDistributionByToken, RewardsRecord, UserState, CONFIG};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_schema::serde::Serialize;
+use cosmwasm_std::{Decimal, DenomUnit, Env, Order, StdResult, Storage, Uint128, Uint64};
+use cw_storage_plus::{Item, KeyDeserialize, Map, PrimaryKey, SnapshotMap, Strategy};
+use std::collections::{BTreeMap, HashMap};
+use symphony_utils::migrate::Migration;
+
+/// State transformers, by the version they migrate to.
+pub const MIGRATIONS: &[Migration<ContractError>] = &[("0.2.0", migrate_to_v0_2)];
+
+#[cw_serde]
+pub struct ConfigV0_1 {
+    pub staking_orchestrator_addr: cosmwasm_std::Addr,
+    pub reward_token: DenomUnit,
+    pub rewards_distribution: Vec<RewardsDistributionByToken>,
+}
+
+#[cw_serde]
+pub struct PoolStateV0_1 {
+    pub denom: DenomUnit,
+    pub total_rewards: Uint128,
+    pub block_height: Uint64,
+}
+
+#[cw_serde]
+pub struct RewardsRecordV0_1 {
+    pub rewards: Uint128,
+}
+
+#[cw_serde]
+pub struct UserStateV0_1 {
+    pub reward_debt: Uint128,
+    pub last_claim_block_height: Uint64,
+    pub rewards_data: HashMap<String, RewardsRecordV0_1>,
+}
+
+/// Value of a snapshot changelog entry, which cw-storage-plus does not export.
+#[cw_serde]
+pub struct ChangeSet<T> {
+    pub old: Option<T>,
+}
+
+pub const CONFIG_V0_1: Item<ConfigV0_1> = Item::new("config");
+pub const POOL_STATE_V0_1: SnapshotMap<&str, PoolStateV0_1> = SnapshotMap::new(
+    "pool_state",
+    "pool_state__checkpoints",
+    "pool_state__changelog",
+    Strategy::EveryBlock,
+);
+pub const USER_STATE_V0_1: SnapshotMap<&cosmwasm_std::Addr, UserStateV0_1> = SnapshotMap::new(
+    "user_state",
+    "user_state__checkpoints",
+    "user_state__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Rewrites every value stored under `namespace` with `transform`.
+fn rewrite_map<K, Old, New>(
+    storage: &mut dyn Storage,
+    namespace: &'static str,
+    transform: impl Fn(Old) -> New,
+) -> StdResult<()>
+where
+    K: for<'a> PrimaryKey<'a> + KeyDeserialize<Output = K> + 'static,
+    Old: Serialize + DeserializeOwned,
+    New: Serialize + DeserializeOwned,
+{
+    let old: Map<K, Old> = Map::new(namespace);
+    let new: Map<K, New> = Map::new(namespace);
+
+    let entries = old
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(K, Old)>>>()?;
+    for (key, value) in entries {
+        new.save(storage, key, &transform(value))?;
+    }
+
+    Ok(())
+}
+
+/// Rewrites the current values and the snapshot history of a snapshot map.
+fn rewrite_snapshot_map<Old, New>(
+    storage: &mut dyn Storage,
+    primary: &'static str,
+    changelog: &'static str,
+    transform: impl Fn(Old) -> New,
+) -> StdResult<()>
+where
+    Old: Serialize + DeserializeOwned,
+    New: Serialize + DeserializeOwned,
+{
+    rewrite_map::<String, Old, New>(storage, primary, &transform)?;
+    rewrite_map::<(String, u64), ChangeSet<Old>, ChangeSet<New>>(storage, changelog, |change| ChangeSet {
+        old: change.old.map(&transform),
+    })
+}
+
+/// Moves from a single reward token credited to every staker on distribution to the reward
+/// per token ledgers. Rewards already credited stay claimable as settled records.
+fn migrate_to_v0_2(storage: &mut dyn Storage, _env: &Env) -> Result<(), ContractError> {
+    let config = CONFIG_V0_1.load(storage)?;
+    let reward_denom = config.reward_token.denom.clone();
+    let distribution = config.rewards_distribution.clone();
+
+    CONFIG.save(storage, &Config {
+        staking_orchestrator_addr: config.staking_orchestrator_addr,
+        reward_tokens: vec![config.reward_token],
+        rewards_distribution: config.rewards_distribution,
+        empty_pool_policy: EmptyPoolPolicy::default(),
+    })?;
+
+    rewrite_snapshot_map(storage, "pool_state", "pool_state__changelog", |pool_state: PoolStateV0_1| {
+        let active = distribution.iter().any(|distro| distro.denom.denom == pool_state.denom.denom);
+        PoolState {
+            denom: pool_state.denom,
+            rewards: BTreeMap::from([(reward_denom.clone(), PoolRewards {
+                total_rewards: pool_state.total_rewards,
+                ..PoolRewards::default()
+            })]),
+            block_height: pool_state.block_height,
+            active,
+            exchange_rate: Decimal::one(),
+            // followed from the first interaction on, so the records keep accruing from zero
+            staking_contract: None,
+            followed_from: BTreeMap::new(),
+            retired_contracts: vec![],
+        }
+    })?;
+
+    rewrite_snapshot_map(storage, "user_state", "user_state__changelog", |user_state: UserStateV0_1| {
+        let mut reward_debt = BTreeMap::new();
+        if !user_state.reward_debt.is_zero() {
+            reward_debt.insert(reward_denom.clone(), user_state.reward_debt);
+        }
+
+        let rewards_data = user_state.rewards_data
+            .into_iter()
+            .map(|(pool_denom, record)| {
+                let record = RewardsRecord { rewards: record.rewards, ..RewardsRecord::default() };
+                (pool_denom, BTreeMap::from([(reward_denom.clone(), record)]))
+            })
+            .collect();
+
+        UserState {
+            reward_debt,
+            last_claim_block_height: user_state.last_claim_block_height,
+            rewards_data,
+        }
+    })?;
+
+    Ok(())
+}
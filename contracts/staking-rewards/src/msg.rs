@@ -1,15 +1,18 @@
-use crate::state::{RewardsDistributionByToken, RewardsRecord};
+use crate::state::{EmptyPoolPolicy, PoolRewards, RewardsDistributionByToken, RewardsRecord};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{DenomUnit, Uint128, Uint64};
+use cosmwasm_std::{Coin, DenomUnit, Timestamp, Uint128, Uint64};
 use cw_ownable::{cw_ownable_execute, cw_ownable_query};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use symphony_interfaces::staking::{SlashHookMsg, StakeChangedHookMsg};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: Option<String>,
     pub staking_orchestrator_addr: String,
-    pub reward_token: DenomUnit,
+    pub reward_tokens: Vec<DenomUnit>,
     pub rewards_distribution: Vec<RewardsDistributionByToken>,
+    /// Defaults to queueing the rewards of empty pools.
+    pub empty_pool_policy: Option<EmptyPoolPolicy>,
 }
 
 #[cw_ownable_execute]
@@ -17,13 +20,22 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     UpdateConfig {
         staking_orchestrator_addr: Option<String>,
-        reward_token: Option<DenomUnit>,
+        reward_tokens: Option<Vec<DenomUnit>>,
         rewards_distribution: Option<Vec<RewardsDistributionByToken>>,
+        empty_pool_policy: Option<EmptyPoolPolicy>,
     },
     DistributeRewards {},
     ClaimRewards {},
     Pause {},
     Unpause {},
+    StakeChangedHook(StakeChangedHookMsg),
+    SlashHook(SlashHookMsg),
+    CreateEmissionSchedule {
+        start: Timestamp,
+        end: Timestamp,
+        total_amount: Uint128,
+    },
+    CancelEmissionSchedule { id: u64 },
 }
 
 #[cw_ownable_query]
@@ -42,20 +54,36 @@ pub enum QueryMsg {
     UserState { address: String, block_height: Option<Uint64> },
     #[returns(IsPausedResponse)]
     IsPaused {},
+    #[returns(EmissionScheduleResponse)]
+    EmissionSchedule { id: u64 },
+    #[returns(ListEmissionSchedulesResponse)]
+    AllEmissionSchedules {},
+    #[returns(RemaindersResponse)]
+    Remainders {},
+    #[returns(SimulateClaimResponse)]
+    SimulateClaim { address: String },
+    #[returns(SolvencyResponse)]
+    Solvency {},
+    /// Whether `denom` is part of the rewards distribution, asked by the orchestrator before
+    /// deregistering it.
+    #[returns(symphony_interfaces::orchestrator::IsRewardedResponse)]
+    IsRewarded { denom: String },
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
     pub staking_orchestrator_addr: String,
-    pub reward_token: DenomUnit,
+    pub reward_tokens: Vec<DenomUnit>,
     pub rewards_distribution: Vec<RewardsDistributionByToken>,
+    pub empty_pool_policy: EmptyPoolPolicy,
 }
 
 #[cw_serde]
 pub struct PoolStateResponse {
     pub denom: DenomUnit,
-    pub total_rewards: Uint128,
+    pub rewards: BTreeMap<String, PoolRewards>,
     pub block_height: Uint64,
+    pub active: bool,
 }
 
 #[cw_serde]
@@ -77,11 +105,71 @@ pub struct PoolStateByDenom {
 #[cw_serde]
 pub struct UserStateResponse {
     pub address: String,
-    pub reward_debt: Uint128,
-    pub rewards_data: HashMap<String, RewardsRecord>,
+    pub reward_debt: Vec<Coin>,
+    pub rewards_data: BTreeMap<String, BTreeMap<String, RewardsRecord>>,
 }
 
 #[cw_serde]
 pub struct IsPausedResponse {
     pub paused: bool,
-}
\ No newline at end of file
+}
+
+#[cw_serde]
+pub struct EmissionScheduleResponse {
+    pub id: u64,
+    pub funder: String,
+    pub denom: String,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub total_amount: Uint128,
+    pub emitted: Uint128,
+}
+
+#[cw_serde]
+pub struct ListEmissionSchedulesResponse {
+    pub emission_schedules: Vec<EmissionScheduleResponse>,
+}
+
+#[cw_serde]
+pub struct PoolRemainders {
+    pub denom: String,
+    pub remainders: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct RemaindersResponse {
+    pub remainders: Vec<PoolRemainders>,
+}
+
+#[cw_serde]
+pub struct PoolClaim {
+    pub denom: String,
+    pub rewards: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct SimulateClaimResponse {
+    pub address: String,
+    pub rewards_by_pool: Vec<PoolClaim>,
+    pub total_rewards: Vec<Coin>,
+    pub can_claim: bool,
+    /// Why the claim would fail, if it would.
+    pub reason: Option<String>,
+}
+
+#[cw_serde]
+pub struct DenomSolvency {
+    pub denom: String,
+    pub liabilities: Uint128,
+    pub balance: Uint128,
+    pub surplus: Uint128,
+    pub deficit: Uint128,
+}
+
+#[cw_serde]
+pub struct SolvencyResponse {
+    pub solvency: Vec<DenomSolvency>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -1,7 +1,7 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, DenomUnit, Uint128, Uint64};
-use cw_storage_plus::{Item, SnapshotMap, Strategy};
-use std::collections::HashMap;
+use cosmwasm_std::{Addr, Decimal, DenomUnit, Timestamp, Uint128, Uint64};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
+use std::collections::BTreeMap;
 
 #[cw_serde]
 pub struct RewardsDistributionByToken {
@@ -9,30 +9,112 @@ pub struct RewardsDistributionByToken {
     pub weight: Uint64,
 }
 
+/// What happens to the share of a distribution assigned to a pool without stakers.
+#[cw_serde]
+#[derive(Default)]
+pub enum EmptyPoolPolicy {
+    /// Keep the rewards in the pool for its first stakers.
+    #[default]
+    Queue,
+    /// Split the rewards between the pools with stakers by their weight.
+    Redistribute,
+    /// Send the rewards back to the distributor.
+    ReturnToSender,
+}
+
+impl std::fmt::Display for EmptyPoolPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmptyPoolPolicy::Queue => write!(f, "queue"),
+            EmptyPoolPolicy::Redistribute => write!(f, "redistribute"),
+            EmptyPoolPolicy::ReturnToSender => write!(f, "return_to_sender"),
+        }
+    }
+}
+
 #[cw_serde]
 pub struct Config {
     pub staking_orchestrator_addr: Addr,
-    pub reward_token: DenomUnit,
+    /// Whitelisted reward tokens.
+    pub reward_tokens: Vec<DenomUnit>,
     pub rewards_distribution: Vec<RewardsDistributionByToken>,
+    pub empty_pool_policy: EmptyPoolPolicy,
+}
+
+/// Rewards ledger of a pool for a single reward denom.
+#[cw_serde]
+#[derive(Default)]
+pub struct PoolRewards {
+    /// Rewards credited to the pool and not yet claimed, including the dust left by rounding
+    /// down each settlement.
+    pub total_rewards: Uint128,
+    /// Accumulated rewards per staked unit since the pool was created.
+    pub reward_per_token: Decimal,
+    /// Rewards of the pool not yet credited to stakers, carried into the next distribution.
+    pub remainder: Uint128,
 }
 
 #[cw_serde]
 pub struct PoolState {
     pub denom: DenomUnit,
-    pub total_rewards: Uint128,
+    /// Ledger per reward denom.
+    pub rewards: BTreeMap<String, PoolRewards>,
     pub block_height: Uint64,
+    /// Whether the pool is part of the rewards distribution. Users keep their accrued
+    /// rewards in sunset pools claimable.
+    pub active: bool,
+    /// Tokens per staked unit, lowered by every slash of the staking contract. Rewards accrue
+    /// per unit so a slash does not shrink the rewards accrued before it.
+    pub exchange_rate: Decimal,
+    /// Staking contract registered for the denom, whose stakers accrue the pool rewards. None
+    /// until the pool first follows one, and once the denom is deregistered.
+    pub staking_contract: Option<Addr>,
+    /// Accumulators per reward denom when the pool started following `staking_contract`.
+    pub followed_from: BTreeMap<String, Decimal>,
+    /// Contracts the pool followed before, whose stakers keep what they accrued meanwhile.
+    pub retired_contracts: Vec<RetiredStakingContract>,
+}
+
+/// Staking contract replaced or deregistered while a pool followed it.
+#[cw_serde]
+pub struct RetiredStakingContract {
+    pub address: Addr,
+    /// Tokens per staked unit of the contract, still lowered by its slashes.
+    pub exchange_rate: Decimal,
+    /// Accumulators per reward denom when the pool started following the contract.
+    pub followed_from: BTreeMap<String, Decimal>,
+    /// Accumulators per reward denom when the pool stopped following it.
+    pub followed_until: BTreeMap<String, Decimal>,
 }
 
 #[cw_serde]
+#[derive(Default)]
 pub struct RewardsRecord {
     pub rewards: Uint128,
+    /// Value of the pool `reward_per_token` when the record was last settled.
+    pub reward_per_token_paid: Decimal,
 }
 
 #[cw_serde]
+#[derive(Default)]
 pub struct UserState {
-    pub reward_debt: Uint128,
+    /// Settled and unclaimed rewards per reward denom.
+    pub reward_debt: BTreeMap<String, Uint128>,
     pub last_claim_block_height: Uint64,
-    pub rewards_data: HashMap<String, RewardsRecord>,
+    /// Checkpoints per pool denom, then per reward denom.
+    pub rewards_data: BTreeMap<String, BTreeMap<String, RewardsRecord>>,
+}
+
+/// Reward tokens streamed linearly into the pools between `start` and `end`.
+#[cw_serde]
+pub struct EmissionSchedule {
+    pub funder: Addr,
+    pub denom: String,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub total_amount: Uint128,
+    /// Part of `total_amount` already added to the pools.
+    pub emitted: Uint128,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -48,4 +130,7 @@ pub const USER_STATE: SnapshotMap<&Addr, UserState> = SnapshotMap::new(
     "user_state__checkpoints",
     "user_state__changelog",
     Strategy::EveryBlock,
-);
\ No newline at end of file
+);
+/// Schedules still emitting. Fully emitted schedules are removed.
+pub const EMISSION_SCHEDULES: Map<u64, EmissionSchedule> = Map::new("emission_schedules");
+pub const EMISSION_SCHEDULE_COUNT: Item<u64> = Item::new("emission_schedule_count");
@@ -1,13 +1,18 @@
 use super::error::ContractError;
 use super::msg::ExecuteMsg::{ClaimRewards, DistributeRewards};
-use super::msg::{AllUserStatesResponse, ConfigResponse, InstantiateMsg, ListPoolStatesResponse, PoolStateResponse, QueryMsg, UserStateResponse};
-use super::state::RewardsDistributionByToken;
-use cosmwasm_std::{coin, Addr, BlockInfo, DenomUnit, Empty, Uint128, Uint64};
+use super::msg::{AllUserStatesResponse, ConfigResponse, DenomSolvency, EmissionScheduleResponse, InstantiateMsg, ListEmissionSchedulesResponse, ListPoolStatesResponse, PoolClaim, PoolRemainders, PoolStateResponse, QueryMsg, RemaindersResponse, SimulateClaimResponse, SolvencyResponse, UserStateResponse};
+use super::migrations::{ConfigV0_1, PoolStateV0_1, RewardsRecordV0_1, UserStateV0_1, CONFIG_V0_1, POOL_STATE_V0_1, USER_STATE_V0_1};
+use super::msg::MigrateMsg;
+use super::state::{EmptyPoolPolicy, PoolRewards, RewardsDistributionByToken, PAUSED};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{coin, to_json_binary, Addr, Binary, BlockInfo, Decimal, DenomUnit, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Uint128, Uint64};
+use std::collections::{BTreeMap, HashMap};
+use cw_controllers::HooksResponse;
 use cw_multi_test::{App, Contract, ContractWrapper, Executor};
 
 const OWNER: &str = "owner";
 const STAKERA: &str = "stakera";
-// const STAKERB: &str = "stakerb";
+const STAKERB: &str = "stakerb";
 const STAKE_DENOM: &str = "ustake";
 const REWARD_DENOM: &str = "urev";
 
@@ -40,7 +45,8 @@ pub fn native_staking_contract() -> Box<dyn Contract<Empty>> {
         native_staking::contract::execute,
         native_staking::contract::instantiate,
         native_staking::contract::query,
-    );
+    )
+        .with_reply(native_staking::contract::reply);
     Box::new(contract)
 }
 
@@ -59,35 +65,119 @@ pub fn staking_rewards_contract() -> Box<dyn Contract<Empty>> {
         super::contract::execute,
         super::contract::instantiate,
         super::contract::query,
+    ).with_migrate(super::contract::migrate);
+    Box::new(contract)
+}
+
+#[cw_serde]
+struct LegacyInstantiateMsg {
+    orchestrator: Addr,
+    staker: Addr,
+}
+
+const LEGACY_STAKER: cw_storage_plus::Item<Addr> = cw_storage_plus::Item::new("legacy_staker");
+
+// credits a distribution the way the first release did, straight into the user state
+fn legacy_credit(deps: DepsMut, height: u64, staker: &Addr, amount: Uint128) -> StdResult<()> {
+    let mut pool_state = POOL_STATE_V0_1.load(deps.storage, STAKE_DENOM)?;
+    pool_state.total_rewards += amount;
+    POOL_STATE_V0_1.save(deps.storage, STAKE_DENOM, &pool_state, height)?;
+
+    let mut user_state = USER_STATE_V0_1.may_load(deps.storage, staker)?.unwrap_or(UserStateV0_1 {
+        reward_debt: Uint128::zero(),
+        last_claim_block_height: Uint64::zero(),
+        rewards_data: HashMap::new(),
+    });
+    user_state.reward_debt += amount;
+    user_state.rewards_data
+        .entry(STAKE_DENOM.to_string())
+        .or_insert(RewardsRecordV0_1 { rewards: Uint128::zero() })
+        .rewards += amount;
+    USER_STATE_V0_1.save(deps.storage, staker, &user_state, height)
+}
+
+// writes the state of the first release
+pub fn legacy_rewards_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        |deps: DepsMut, env: Env, _info: MessageInfo, _msg: Empty| -> StdResult<Response> {
+            let staker = LEGACY_STAKER.load(deps.storage)?;
+            legacy_credit(deps, env.block.height, &staker, Uint128::new(30))?;
+            Ok(Response::new())
+        },
+        |deps: DepsMut, env: Env, info: MessageInfo, msg: LegacyInstantiateMsg| -> StdResult<Response> {
+            cw2::set_contract_version(deps.storage, "crates.io:symphony-staking-rewards", "0.1.0")?;
+            cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
+            let stake_denom = DenomUnit {
+                denom: STAKE_DENOM.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            };
+            CONFIG_V0_1.save(deps.storage, &ConfigV0_1 {
+                staking_orchestrator_addr: msg.orchestrator,
+                reward_token: DenomUnit {
+                    denom: REWARD_DENOM.to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+                rewards_distribution: vec![RewardsDistributionByToken {
+                    denom: stake_denom.clone(),
+                    weight: Uint64::one(),
+                }],
+            })?;
+            POOL_STATE_V0_1.save(deps.storage, STAKE_DENOM, &PoolStateV0_1 {
+                denom: stake_denom,
+                total_rewards: Uint128::zero(),
+                block_height: Uint64::new(env.block.height),
+            }, env.block.height)?;
+            PAUSED.save(deps.storage, &false)?;
+            LEGACY_STAKER.save(deps.storage, &msg.staker)?;
+
+            legacy_credit(deps, env.block.height, &msg.staker, Uint128::new(50))?;
+            Ok(Response::new())
+        },
+        |_deps: Deps, _env: Env, _msg: Empty| -> StdResult<Binary> {
+            to_json_binary(&Empty {})
+        },
     );
     Box::new(contract)
 }
 
 fn instantiate_orchestrator(app: &mut App, denom: &str) -> Addr {
+    let orchestrator_addr = instantiate_empty_orchestrator(app);
+    create_staking_contract(app, &orchestrator_addr, denom);
+
+    orchestrator_addr
+}
+
+fn instantiate_empty_orchestrator(app: &mut App) -> Addr {
     let owner = app.api().addr_make(OWNER);
-    let staking_code_id = app.store_code(native_staking_contract());
     let orchestrator_code_id = app.store_code(staking_orchestrator_contract());
 
     let msg = staking_orchestrator::msg::InstantiateMsg {
         owner: Some(owner.to_string()),
     };
 
-    let orchestrator_addr = app.instantiate_contract(
+    app.instantiate_contract(
         orchestrator_code_id,
-        owner.clone(),
+        owner,
         &msg,
         &[],
         "orchestrator",
         Some(app.api().addr_make("admin").into()),
-    ).unwrap();
+    ).unwrap()
+}
+
+fn create_staking_contract(app: &mut App, orchestrator_addr: &Addr, denom: &str) {
+    let owner = app.api().addr_make(OWNER);
+    let staking_code_id = app.store_code(native_staking_contract());
 
     let execute_msg = staking_orchestrator::msg::ExecuteMsg::CreateStakingContract {
         code_id: staking_code_id,
-        denom_unit: DenomUnit {
+        token: symphony_interfaces::orchestrator::StakingToken::Native(DenomUnit {
             denom: denom.to_string(),
             exponent: 6,
             aliases: vec![],
-        },
+        }),
         unbonding_period: None,
         owner: Some(owner.to_string())
     };
@@ -98,21 +188,20 @@ fn instantiate_orchestrator(app: &mut App, denom: &str) -> Addr {
         &execute_msg,
         &[],
     ).unwrap();
-
-    orchestrator_addr
 }
 
-fn instantiate_rewards(app: &mut App, owner: Option<String>, orchestrator_addr: &Addr, reward_denom: &DenomUnit, rewards_distribution: &Vec<RewardsDistributionByToken>) -> Addr {
+fn instantiate_rewards(app: &mut App, owner: Option<String>, orchestrator_addr: &Addr, reward_denom: &DenomUnit, rewards_distribution: &[RewardsDistributionByToken]) -> Addr {
     let rewards_code_id = app.store_code(staking_rewards_contract());
     let msg = InstantiateMsg {
         owner,
         staking_orchestrator_addr: orchestrator_addr.into(),
-        reward_token: reward_denom.clone(),
-        rewards_distribution: rewards_distribution.clone(),
+        reward_tokens: vec![reward_denom.clone()],
+        rewards_distribution: rewards_distribution.to_vec(),
+        empty_pool_policy: None,
     };
     app.instantiate_contract(
         rewards_code_id,
-        app.api().addr_make(OWNER).into(),
+        app.api().addr_make(OWNER),
         &msg,
         &[],
         "staking_rewards",
@@ -125,8 +214,8 @@ fn instantiate_rewards(app: &mut App, owner: Option<String>, orchestrator_addr:
 pub fn staking_rewards_instantiate() {
     let mut app = mock_app();
     let owner_address = app.api().addr_make(OWNER);
-    mint_native(&mut app, &owner_address.to_string(), "urev", 1_000_000_000);
-    let orchestrator_contract = app.api().addr_make("orchestrator");
+    mint_native(&mut app, owner_address.as_ref(), "urev", 1_000_000_000);
+    let orchestrator_contract = instantiate_orchestrator(&mut app, "ustake");
     let reward_denom = DenomUnit {
         denom: "urev".to_string(),
         exponent: 6,
@@ -168,8 +257,9 @@ pub fn staking_rewards_instantiate() {
     ).unwrap();
 
     assert_eq!(config.staking_orchestrator_addr, orchestrator_contract.into_string());
-    assert_eq!(config.reward_token, reward_denom);
+    assert_eq!(config.reward_tokens, vec![reward_denom]);
     assert_eq!(config.rewards_distribution, rewards_distribution);
+    assert_eq!(config.empty_pool_policy, EmptyPoolPolicy::Queue);
 
     let pool_states: ListPoolStatesResponse = app.wrap().query_wasm_smart(
         rewards_contract.clone(),
@@ -203,6 +293,22 @@ fn stake_some_tokens(app: &mut App, user: &Addr, orchestrator_addr: &Addr, denom
     ).unwrap();
 }
 
+fn add_rewards_hook(app: &mut App, orchestrator_addr: &Addr, denom: &str, rewards_addr: &Addr) {
+    let response: symphony_interfaces::orchestrator::StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_addr.clone(),
+        &symphony_interfaces::orchestrator::QueryMsg::StakingContractByDenom {
+            denom: denom.to_string(),
+        },
+    ).unwrap();
+
+    app.execute_contract(
+        app.api().addr_make(OWNER),
+        Addr::unchecked(response.registered_contract.address),
+        &symphony_interfaces::staking::ExecuteMsg::AddHook { addr: rewards_addr.to_string() },
+        &[],
+    ).unwrap();
+}
+
 #[test]
 pub fn distribute_rewards() {
     let mut app = mock_app();
@@ -239,6 +345,7 @@ pub fn distribute_rewards() {
 
     stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
     next_block(&mut app);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
 
     mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_000_000);
 
@@ -259,13 +366,13 @@ pub fn distribute_rewards() {
         &[coin(1_000_000, REWARD_DENOM)],
     ).unwrap();
 
+    // rewards are settled lazily, so distributing does not write any user state
     let user_states: AllUserStatesResponse = app.wrap().query_wasm_smart(
         rewards_contract.clone(),
         &QueryMsg::AllUserStates {},
     ).unwrap();
 
-    assert_eq!(user_states.user_states.len(), 1);
-    assert_eq!(user_states.user_states[0].reward_debt, Uint128::from(1_000_000u128));
+    assert_eq!(user_states.user_states.len(), 0);
 
     let pool_state: PoolStateResponse = app.wrap().query_wasm_smart(
         rewards_contract.clone(),
@@ -275,7 +382,8 @@ pub fn distribute_rewards() {
         },
     ).unwrap();
 
-    assert_eq!(pool_state.total_rewards, Uint128::from(1_000_000u128));
+    assert_eq!(pool_state.rewards[REWARD_DENOM].total_rewards, Uint128::from(1_000_000u128));
+    assert_eq!(pool_state.rewards[REWARD_DENOM].reward_per_token, Decimal::from_ratio(1_000_000u128, 100u128));
 
     let user_state: UserStateResponse = app.wrap().query_wasm_smart(
         rewards_contract.clone(),
@@ -286,7 +394,7 @@ pub fn distribute_rewards() {
     ).unwrap();
 
     println!("{:?}", user_state);
-    assert_eq!(user_state.reward_debt, Uint128::from(1_000_000u128));
+    assert_eq!(user_state.reward_debt, vec![coin(1_000_000, REWARD_DENOM)]);
     next_block(&mut app);
 
     let msg = ClaimRewards {};
@@ -305,7 +413,7 @@ pub fn distribute_rewards() {
         },
     ).unwrap();
 
-    assert_eq!(user_state.reward_debt, Uint128::zero());
+    assert!(user_state.reward_debt.is_empty());
 
     // check staker_a balance
     let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
@@ -319,15 +427,15 @@ pub fn distribute_rewards() {
         },
     ).unwrap();
 
-    assert_eq!(pool_state.total_rewards, Uint128::zero());
+    assert_eq!(pool_state.rewards[REWARD_DENOM].total_rewards, Uint128::zero());
 }
 
 #[test]
 pub fn paused_contract_should_not_distribute_rewards() {
     let mut app = mock_app();
     let owner_address = app.api().addr_make(OWNER);
-    mint_native(&mut app, &owner_address.to_string(), "urev", 1_000_000_000);
-    let orchestrator_contract = app.api().addr_make("orchestrator");
+    mint_native(&mut app, owner_address.as_ref(), "urev", 1_000_000_000);
+    let orchestrator_contract = instantiate_orchestrator(&mut app, "ustake");
     let reward_denom = DenomUnit {
         denom: "urev".to_string(),
         exponent: 6,
@@ -388,8 +496,8 @@ pub fn paused_contract_should_not_distribute_rewards() {
 pub fn paused_contract_should_not_claim_rewards() {
     let mut app = mock_app();
     let owner_address = app.api().addr_make(OWNER);
-    mint_native(&mut app, &owner_address.to_string(), "urev", 1_000_000_000);
-    let orchestrator_contract = app.api().addr_make("orchestrator");
+    mint_native(&mut app, owner_address.as_ref(), "urev", 1_000_000_000);
+    let orchestrator_contract = instantiate_orchestrator(&mut app, "ustake");
     let reward_denom = DenomUnit {
         denom: "urev".to_string(),
         exponent: 6,
@@ -444,4 +552,1531 @@ pub fn paused_contract_should_not_claim_rewards() {
     ).unwrap_err();
 
     assert_eq!(err.root_cause().to_string(), ContractError::ContractPaused {}.to_string());
-}
\ No newline at end of file
+}
+#[test]
+pub fn distribute_rewards_should_split_by_stake() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+
+    let reward_denom = DenomUnit {
+        denom: REWARD_DENOM.to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let rewards_distribution = vec![
+        RewardsDistributionByToken {
+            denom: DenomUnit {
+                denom: STAKE_DENOM.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            weight: Uint64::from(100_000u64),
+        },
+    ];
+
+    let rewards_contract = instantiate_rewards(
+        &mut app,
+        Some(owner_address.to_string()),
+        &orchestrator_addr,
+        &reward_denom,
+        &rewards_distribution,
+    );
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 300);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 2_000_000);
+
+    // two distributions only update the pool accumulator
+    for _ in 0..2 {
+        app.execute_contract(
+            owner_address.clone(),
+            rewards_contract.clone(),
+            &DistributeRewards {},
+            &[coin(1_000_000, REWARD_DENOM)],
+        ).unwrap();
+        next_block(&mut app);
+    }
+
+    let user_state: UserStateResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::UserState {
+            address: staker_b.to_string(),
+            block_height: None,
+        },
+    ).unwrap();
+    assert_eq!(user_state.reward_debt, vec![coin(500_000, REWARD_DENOM)]);
+
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    app.execute_contract(staker_b.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::from(1_500_000u128));
+    let balance = app.wrap().query_balance(staker_b.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::from(500_000u128));
+
+    // a second claim without new distributions has nothing to pay
+    let err = app.execute_contract(
+        staker_a.clone(),
+        rewards_contract.clone(),
+        &ClaimRewards {},
+        &[],
+    ).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), ContractError::NoRewardsToClaim {}.to_string());
+
+    let pool_state: PoolStateResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::PoolState {
+            denom: STAKE_DENOM.to_string(),
+            block_height: None,
+        },
+    ).unwrap();
+    assert_eq!(pool_state.rewards[REWARD_DENOM].total_rewards, Uint128::zero());
+}
+
+#[test]
+pub fn stake_changes_should_checkpoint_rewards() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+
+    let reward_denom = DenomUnit {
+        denom: REWARD_DENOM.to_string(),
+        exponent: 6,
+        aliases: vec![],
+    };
+    let rewards_distribution = vec![
+        RewardsDistributionByToken {
+            denom: DenomUnit {
+                denom: STAKE_DENOM.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            weight: Uint64::from(100_000u64),
+        },
+    ];
+
+    let rewards_contract = instantiate_rewards(
+        &mut app,
+        Some(owner_address.to_string()),
+        &orchestrator_addr,
+        &reward_denom,
+        &rewards_distribution,
+    );
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 2_000_000);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    // staker b joins after the first distribution and must not earn from it
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    // staker a leaves, keeping the rewards accrued so far
+    let staking_contract: symphony_interfaces::orchestrator::StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_addr.clone(),
+        &symphony_interfaces::orchestrator::QueryMsg::StakingContractByDenom {
+            denom: STAKE_DENOM.to_string(),
+        },
+    ).unwrap();
+    app.execute_contract(
+        staker_a.clone(),
+        Addr::unchecked(staking_contract.registered_contract.address),
+        &symphony_interfaces::staking::ExecuteMsg::Unstake { amount: Uint128::new(100), owner: None },
+        &[],
+    ).unwrap();
+    next_block(&mut app);
+
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    app.execute_contract(staker_b.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::from(1_500_000u128));
+    let balance = app.wrap().query_balance(staker_b.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::from(500_000u128));
+}
+
+#[test]
+pub fn stake_in_the_distribution_block_should_share_the_distribution() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    // staker b stakes earlier in the same block as the distribution
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_000_000);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+
+    for staker in [&staker_a, &staker_b] {
+        let claim: SimulateClaimResponse = app.wrap().query_wasm_smart(
+            rewards_contract.clone(),
+            &QueryMsg::SimulateClaim { address: staker.to_string() },
+        ).unwrap();
+        assert_eq!(claim.total_rewards, vec![coin(500_000, REWARD_DENOM)]);
+    }
+
+    app.execute_contract(staker_b.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::from(500_000u128));
+    let balance = app.wrap().query_balance(staker_b.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::from(500_000u128));
+}
+
+#[test]
+pub fn slash_should_keep_the_rewards_accrued_before_it() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 2_000_000);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    let staking_contract: symphony_interfaces::orchestrator::StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_addr.clone(),
+        &symphony_interfaces::orchestrator::QueryMsg::StakingContractByDenom {
+            denom: STAKE_DENOM.to_string(),
+        },
+    ).unwrap();
+    app.execute_contract(
+        owner_address.clone(),
+        Addr::unchecked(staking_contract.registered_contract.address),
+        &symphony_interfaces::staking::ExecuteMsg::Slash {
+            ratio: Decimal::percent(50),
+            recipient: owner_address.to_string(),
+        },
+        &[],
+    ).unwrap();
+    next_block(&mut app);
+
+    // balances halved, but the rewards distributed before the slash are intact
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(500_000));
+
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    app.execute_contract(staker_b.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1_000_000));
+    let balance = app.wrap().query_balance(staker_b.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1_000_000));
+
+    // nothing is left stranded in the pool
+    let pool_state: PoolStateResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::PoolState {
+            denom: STAKE_DENOM.to_string(),
+            block_height: None,
+        },
+    ).unwrap();
+    assert_eq!(pool_state.rewards[REWARD_DENOM].total_rewards, Uint128::zero());
+}
+
+#[test]
+pub fn stake_changed_hook_should_only_accept_registered_contract() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+
+    let rewards_contract = instantiate_rewards(
+        &mut app,
+        Some(owner_address.to_string()),
+        &orchestrator_addr,
+        &DenomUnit {
+            denom: REWARD_DENOM.to_string(),
+            exponent: 6,
+            aliases: vec![],
+        },
+        &[RewardsDistributionByToken {
+            denom: DenomUnit {
+                denom: STAKE_DENOM.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            weight: Uint64::from(100_000u64),
+        }],
+    );
+
+    let msg = super::msg::ExecuteMsg::StakeChangedHook(symphony_interfaces::staking::StakeChangedHookMsg {
+        addr: owner_address.to_string(),
+        old_balance: Uint128::new(1_000),
+        new_balance: Uint128::zero(),
+        denom: STAKE_DENOM.to_string(),
+    });
+    let err = app.execute_contract(owner_address, rewards_contract, &msg, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), ContractError::Unauthorized {}.to_string());
+}
+
+#[test]
+pub fn unhooked_staking_contracts_should_not_accrue_rewards() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 2_000);
+    let distribute = |app: &mut App| app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000, REWARD_DENOM)],
+    ).unwrap();
+
+    // the stakes are not checkpointed, so the distribution is queued rather than accrued
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+    distribute(&mut app);
+    next_block(&mut app);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    for staker in [&staker_a, &staker_b] {
+        let err = app.execute_contract(staker.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap_err();
+        assert_eq!(err.root_cause().to_string(), ContractError::NoRewardsToClaim {}.to_string());
+    }
+
+    // once hooked the queued rewards go to the stakers of the next distribution
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+    distribute(&mut app);
+    next_block(&mut app);
+
+    for staker in [&staker_a, &staker_b] {
+        app.execute_contract(staker.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+        let balance = app.wrap().query_balance(staker, REWARD_DENOM).unwrap();
+        assert_eq!(balance.amount, Uint128::new(1_000));
+    }
+}
+
+#[test]
+pub fn orchestrator_should_wire_rewards_into_new_staking_contracts() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_empty_orchestrator(&mut app);
+    let rewards_contract = instantiate_rewards(
+        &mut app,
+        Some(owner_address.to_string()),
+        &orchestrator_addr,
+        &DenomUnit {
+            denom: REWARD_DENOM.to_string(),
+            exponent: 6,
+            aliases: vec![],
+        },
+        &[RewardsDistributionByToken {
+            denom: DenomUnit {
+                denom: STAKE_DENOM.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            weight: Uint64::from(100_000u64),
+        }],
+    );
+
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_addr.clone(),
+        &staking_orchestrator::msg::ExecuteMsg::SetRewardsContract {
+            address: rewards_contract.to_string(),
+        },
+        &[],
+    ).unwrap();
+    create_staking_contract(&mut app, &orchestrator_addr, STAKE_DENOM);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_000_000);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    // the hook checkpoints staker b at the current accumulator
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    let user_state: UserStateResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::UserState {
+            address: staker_b.to_string(),
+            block_height: None,
+        },
+    ).unwrap();
+    assert!(user_state.reward_debt.is_empty());
+
+    let user_state: UserStateResponse = app.wrap().query_wasm_smart(
+        rewards_contract,
+        &QueryMsg::UserState {
+            address: staker_a.to_string(),
+            block_height: None,
+        },
+    ).unwrap();
+    assert_eq!(user_state.reward_debt, vec![coin(1_000_000, REWARD_DENOM)]);
+}
+
+fn staking_contract_by_denom(app: &App, orchestrator_addr: &Addr, denom: &str) -> Addr {
+    let response: symphony_interfaces::orchestrator::StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+        orchestrator_addr.clone(),
+        &symphony_interfaces::orchestrator::QueryMsg::StakingContractByDenom {
+            denom: denom.to_string(),
+        },
+    ).unwrap();
+    Addr::unchecked(response.registered_contract.address)
+}
+
+fn unstake_tokens(app: &mut App, user: &Addr, staking_contract: &Addr, amount: u128) {
+    app.execute_contract(
+        user.clone(),
+        staking_contract.clone(),
+        &symphony_interfaces::staking::ExecuteMsg::Unstake {
+            amount: Uint128::new(amount),
+            owner: None,
+        },
+        &[],
+    ).unwrap();
+}
+
+#[test]
+pub fn retired_staking_contracts_should_still_unstake() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+    let stable_denom = "ustable";
+
+    let orchestrator_addr = instantiate_empty_orchestrator(&mut app);
+    let rewards_distribution = |denoms: &[&str]| denoms
+        .iter()
+        .map(|denom| RewardsDistributionByToken {
+            denom: DenomUnit {
+                denom: denom.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            weight: Uint64::from(100_000u64 / denoms.len() as u64),
+        })
+        .collect::<Vec<_>>();
+    let rewards_contract = instantiate_rewards(
+        &mut app,
+        Some(owner_address.to_string()),
+        &orchestrator_addr,
+        &DenomUnit {
+            denom: REWARD_DENOM.to_string(),
+            exponent: 6,
+            aliases: vec![],
+        },
+        &rewards_distribution(&[STAKE_DENOM, stable_denom]),
+    );
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_addr.clone(),
+        &staking_orchestrator::msg::ExecuteMsg::SetRewardsContract {
+            address: rewards_contract.to_string(),
+        },
+        &[],
+    ).unwrap();
+
+    // owned by the orchestrator
+    let staking_code_id = app.store_code(native_staking_contract());
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_addr.clone(),
+        &staking_orchestrator::msg::ExecuteMsg::CreateStakingContract {
+            code_id: staking_code_id,
+            token: symphony_interfaces::orchestrator::StakingToken::Native(DenomUnit {
+                denom: STAKE_DENOM.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            }),
+            unbonding_period: None,
+            owner: None,
+        },
+        &[],
+    ).unwrap();
+    create_staking_contract(&mut app, &orchestrator_addr, stable_denom);
+    let deprecated_contract = staking_contract_by_denom(&app, &orchestrator_addr, STAKE_DENOM);
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    // the stable pool has no stakers, so its half is queued
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 2_000);
+    let distribute = |app: &mut App| app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000, REWARD_DENOM)],
+    ).unwrap();
+    distribute(&mut app);
+    next_block(&mut app);
+
+    // not owned by the orchestrator, so it must notify the rewards contract already
+    let closed_contract = app.instantiate_contract(
+        staking_code_id,
+        owner_address.clone(),
+        &symphony_interfaces::staking::InstantiateMsg {
+            owner: None,
+            denom_unit: DenomUnit {
+                denom: STAKE_DENOM.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            unbonding_period: None,
+            hooks: Some(vec![rewards_contract.to_string()]),
+            dao: None,
+        },
+        &[],
+        "staking",
+        None,
+    ).unwrap();
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_addr.clone(),
+        &staking_orchestrator::msg::ExecuteMsg::ReplaceStakingContract {
+            address: closed_contract.to_string(),
+        },
+        &[],
+    ).unwrap();
+
+    // the deprecated contract keeps notifying, so its stakers still settle their rewards
+    let hooks: HooksResponse = app.wrap().query_wasm_smart(
+        deprecated_contract.clone(),
+        &symphony_interfaces::staking::QueryMsg::Hooks {},
+    ).unwrap();
+    assert_eq!(hooks.hooks, vec![rewards_contract.to_string()]);
+
+    // only the stakers of the new contract accrue from the replacement on
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+    distribute(&mut app);
+    next_block(&mut app);
+
+    unstake_tokens(&mut app, &staker_a, &deprecated_contract, 100);
+    next_block(&mut app);
+
+    for staker in [&staker_a, &staker_b] {
+        app.execute_contract(staker.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+        let balance = app.wrap().query_balance(staker, REWARD_DENOM).unwrap();
+        assert_eq!(balance.amount, Uint128::new(500));
+    }
+
+    let deregister = |app: &mut App| app.execute_contract(
+        owner_address.clone(),
+        orchestrator_addr.clone(),
+        &staking_orchestrator::msg::ExecuteMsg::DeregisterStakingContract {
+            denom: STAKE_DENOM.to_string(),
+        },
+        &[],
+    );
+    let err = deregister(&mut app).unwrap_err();
+    assert_eq!(
+        err.downcast::<staking_orchestrator::error::ContractError>().unwrap(),
+        staking_orchestrator::error::ContractError::DenomRewarded { denom: STAKE_DENOM.to_string() },
+    );
+
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &super::msg::ExecuteMsg::UpdateConfig {
+            staking_orchestrator_addr: None,
+            reward_tokens: None,
+            rewards_distribution: Some(rewards_distribution(&[stable_denom])),
+            empty_pool_policy: None,
+        },
+        &[],
+    ).unwrap();
+    deregister(&mut app).unwrap();
+
+    let hooks: HooksResponse = app.wrap().query_wasm_smart(
+        closed_contract.clone(),
+        &symphony_interfaces::staking::QueryMsg::Hooks {},
+    ).unwrap();
+    assert_eq!(hooks.hooks, vec![rewards_contract.to_string()]);
+    unstake_tokens(&mut app, &staker_b, &closed_contract, 100);
+
+    assert_eq!(app.wrap().query_balance(&staker_a, STAKE_DENOM).unwrap().amount, Uint128::new(100));
+    assert_eq!(app.wrap().query_balance(&staker_b, STAKE_DENOM).unwrap().amount, Uint128::new(100));
+}
+
+#[test]
+pub fn distribution_should_treat_deregistered_pools_as_empty() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+    let stable_denom = "ustable";
+
+    // the orchestrator does not know the rewards contract, so it cannot refuse deregistering
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    create_staking_contract(&mut app, &orchestrator_addr, stable_denom);
+    let rewards_contract = instantiate_rewards(
+        &mut app,
+        Some(owner_address.to_string()),
+        &orchestrator_addr,
+        &DenomUnit {
+            denom: REWARD_DENOM.to_string(),
+            exponent: 6,
+            aliases: vec![],
+        },
+        &[STAKE_DENOM, stable_denom].map(|denom| RewardsDistributionByToken {
+            denom: DenomUnit {
+                denom: denom.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            weight: Uint64::from(50_000u64),
+        }),
+    );
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+    add_rewards_hook(&mut app, &orchestrator_addr, stable_denom, &rewards_contract);
+
+    let closed_contract = staking_contract_by_denom(&app, &orchestrator_addr, STAKE_DENOM);
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, stable_denom, 100);
+    next_block(&mut app);
+
+    app.execute_contract(
+        owner_address.clone(),
+        orchestrator_addr.clone(),
+        &staking_orchestrator::msg::ExecuteMsg::DeregisterStakingContract {
+            denom: STAKE_DENOM.to_string(),
+        },
+        &[],
+    ).unwrap();
+    unstake_tokens(&mut app, &staker_a, &closed_contract, 100);
+    next_block(&mut app);
+
+    // the share of the deregistered pool is queued, as for any pool without stakers
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_000);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000, REWARD_DENOM)],
+    ).unwrap();
+
+    app.execute_contract(staker_b.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    let balance = app.wrap().query_balance(staker_b, REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(500));
+}
+
+fn instantiate_single_pool_rewards(app: &mut App, orchestrator_addr: &Addr) -> Addr {
+    let owner_address = app.api().addr_make(OWNER);
+    instantiate_rewards(
+        app,
+        Some(owner_address.to_string()),
+        orchestrator_addr,
+        &DenomUnit {
+            denom: REWARD_DENOM.to_string(),
+            exponent: 6,
+            aliases: vec![],
+        },
+        &[RewardsDistributionByToken {
+            denom: DenomUnit {
+                denom: STAKE_DENOM.to_string(),
+                exponent: 6,
+                aliases: vec![],
+            },
+            weight: Uint64::from(100_000u64),
+        }],
+    )
+}
+
+#[test]
+pub fn emission_schedule_should_stream_rewards() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 2_000_000);
+
+    let start = app.block_info().time;
+    let msg = super::msg::ExecuteMsg::CreateEmissionSchedule {
+        start,
+        end: start.plus_seconds(100),
+        total_amount: Uint128::new(1_000_000),
+    };
+
+    let err = app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &msg,
+        &[coin(999_999, REWARD_DENOM)],
+    ).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), ContractError::InvalidEmissionFunds {}.to_string());
+
+    let err = app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &super::msg::ExecuteMsg::CreateEmissionSchedule {
+            start,
+            end: start,
+            total_amount: Uint128::new(1_000_000),
+        },
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), ContractError::InvalidEmissionSchedule {}.to_string());
+
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &msg,
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+
+    // 4 blocks of 5 seconds vest 20% of the schedule
+    for _ in 0..4 {
+        next_block(&mut app);
+    }
+
+    let schedule: EmissionScheduleResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::EmissionSchedule { id: 1 },
+    ).unwrap();
+    assert_eq!(schedule.emitted, Uint128::new(200_000));
+
+    let user_state: UserStateResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::UserState {
+            address: staker_a.to_string(),
+            block_height: None,
+        },
+    ).unwrap();
+    assert_eq!(user_state.reward_debt, vec![coin(200_000, REWARD_DENOM)]);
+
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(200_000));
+
+    // past the end everything is emitted exactly once
+    for _ in 0..30 {
+        next_block(&mut app);
+    }
+
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1_000_000));
+
+    // the finished schedule is removed
+    let schedules: ListEmissionSchedulesResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::AllEmissionSchedules {},
+    ).unwrap();
+    assert!(schedules.emission_schedules.is_empty());
+    app.wrap().query_wasm_smart::<EmissionScheduleResponse>(
+        rewards_contract,
+        &QueryMsg::EmissionSchedule { id: 1 },
+    ).unwrap_err();
+}
+
+#[test]
+pub fn emissions_should_accrue_over_the_stake_before_a_change() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+    let staking_contract = staking_contract_by_denom(&app, &orchestrator_addr, STAKE_DENOM);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_000);
+
+    let start = app.block_info().time.plus_seconds(TIME_BETWEEN_BLOCKS);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &super::msg::ExecuteMsg::CreateEmissionSchedule {
+            start,
+            end: start.plus_seconds(TIME_BETWEEN_BLOCKS),
+            total_amount: Uint128::new(1_000),
+        },
+        &[coin(1_000, REWARD_DENOM)],
+    ).unwrap();
+    for _ in 0..3 {
+        next_block(&mut app);
+    }
+
+    // the whole emission vested while both stakers held 100
+    unstake_tokens(&mut app, &staker_b, &staking_contract, 100);
+    next_block(&mut app);
+
+    for staker in [&staker_b, &staker_a] {
+        app.execute_contract(staker.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+        let balance = app.wrap().query_balance(staker, REWARD_DENOM).unwrap();
+        assert_eq!(balance.amount, Uint128::new(500));
+    }
+}
+
+#[test]
+pub fn cancel_emission_schedule_should_refund_unvested() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_000_000);
+
+    let start = app.block_info().time.plus_seconds(TIME_BETWEEN_BLOCKS);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &super::msg::ExecuteMsg::CreateEmissionSchedule {
+            start,
+            end: start.plus_seconds(100),
+            total_amount: Uint128::new(1_000_000),
+        },
+        &[coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+
+    // emission has not started yet
+    next_block(&mut app);
+    let schedule: EmissionScheduleResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::EmissionSchedule { id: 1 },
+    ).unwrap();
+    assert_eq!(schedule.emitted, Uint128::zero());
+
+    for _ in 0..5 {
+        next_block(&mut app);
+    }
+
+    let msg = super::msg::ExecuteMsg::CancelEmissionSchedule { id: 1 };
+    let err = app.execute_contract(staker_a.clone(), rewards_contract.clone(), &msg, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), cw_ownable::OwnershipError::NotOwner.to_string());
+
+    app.execute_contract(owner_address.clone(), rewards_contract.clone(), &msg, &[]).unwrap();
+
+    let balance = app.wrap().query_balance(owner_address.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(750_000));
+
+    // the vested part stays claimable
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(250_000));
+
+    let schedules: ListEmissionSchedulesResponse = app.wrap().query_wasm_smart(
+        rewards_contract,
+        &QueryMsg::AllEmissionSchedules {},
+    ).unwrap();
+    assert!(schedules.emission_schedules.is_empty());
+}
+
+#[test]
+pub fn distribute_and_claim_multiple_reward_tokens() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let partner_denom = "upartner";
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_000_000);
+    mint_native(&mut app, owner_address.as_str(), partner_denom, 500_000);
+
+    // the partner token is not whitelisted yet
+    let err = app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(500_000, partner_denom), coin(1_000_000, REWARD_DENOM)],
+    ).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::InvalidRewardDenom { denom: partner_denom.to_string() }.to_string()
+    );
+
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &super::msg::ExecuteMsg::UpdateConfig {
+            staking_orchestrator_addr: None,
+            reward_tokens: Some(vec![
+                DenomUnit {
+                    denom: REWARD_DENOM.to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+                DenomUnit {
+                    denom: partner_denom.to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+            ]),
+            rewards_distribution: None,
+            empty_pool_policy: None,
+        },
+        &[],
+    ).unwrap();
+
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(500_000, partner_denom), coin(1_000_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    let pool_state: PoolStateResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::PoolState {
+            denom: STAKE_DENOM.to_string(),
+            block_height: None,
+        },
+    ).unwrap();
+    assert_eq!(pool_state.rewards[REWARD_DENOM].total_rewards, Uint128::new(1_000_000));
+    assert_eq!(pool_state.rewards[partner_denom].total_rewards, Uint128::new(500_000));
+
+    let user_state: UserStateResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::UserState {
+            address: staker_a.to_string(),
+            block_height: None,
+        },
+    ).unwrap();
+    assert_eq!(user_state.reward_debt, vec![coin(500_000, partner_denom), coin(1_000_000, REWARD_DENOM)]);
+
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1_000_000));
+    let balance = app.wrap().query_balance(staker_a.clone(), partner_denom).unwrap();
+    assert_eq!(balance.amount, Uint128::new(500_000));
+}
+
+#[test]
+pub fn distribution_remainders_should_carry_forward() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 2);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 1);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 12);
+
+    // 10 over 3 staked tokens cannot be credited exactly
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(10, REWARD_DENOM)],
+    ).unwrap();
+
+    let remainders: RemaindersResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::Remainders {},
+    ).unwrap();
+    assert_eq!(remainders.remainders, vec![PoolRemainders {
+        denom: STAKE_DENOM.to_string(),
+        remainders: vec![coin(1, REWARD_DENOM)],
+    }]);
+    next_block(&mut app);
+
+    // the remainder is added to the next distribution
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(2, REWARD_DENOM)],
+    ).unwrap();
+
+    let remainders: RemaindersResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::Remainders {},
+    ).unwrap();
+    assert!(remainders.remainders[0].remainders.is_empty());
+
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    app.execute_contract(staker_b.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+
+    let balance = app.wrap().query_balance(staker_a, REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(8));
+    let balance = app.wrap().query_balance(staker_b, REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(4));
+    let balance = app.wrap().query_balance(rewards_contract, REWARD_DENOM).unwrap();
+    assert!(balance.amount.is_zero());
+}
+
+#[test]
+pub fn settlement_dust_should_stay_in_the_balance_within_the_bound() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 1);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 1);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 3);
+
+    // 1.5 per staked token is credited exactly, but each staked token settles to 1
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(3, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    for staker in [&staker_a, &staker_b] {
+        app.execute_contract(staker.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    }
+
+    let balance = app.wrap().query_balance(staker_a, REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::one());
+    let balance = app.wrap().query_balance(staker_b, REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::one());
+
+    let remainders: RemaindersResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::Remainders {},
+    ).unwrap();
+    assert!(remainders.remainders[0].remainders.is_empty());
+
+    // the dust is under one token per settlement and stays owed, so the contract is solvent
+    let balance = app.wrap().query_balance(rewards_contract.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::one());
+    let solvency: SolvencyResponse = app.wrap().query_wasm_smart(
+        rewards_contract,
+        &QueryMsg::Solvency {},
+    ).unwrap();
+    let reward_solvency = solvency.solvency
+        .iter()
+        .find(|solvency| solvency.denom == REWARD_DENOM)
+        .unwrap();
+    assert_eq!(reward_solvency.liabilities, balance.amount);
+    assert!(reward_solvency.deficit.is_zero());
+}
+
+#[test]
+pub fn empty_pool_policy_should_apply_to_pools_without_stakers() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+    let empty_denom = "ustable";
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    create_staking_contract(&mut app, &orchestrator_addr, empty_denom);
+
+    let rewards_contract = instantiate_rewards(
+        &mut app,
+        Some(owner_address.to_string()),
+        &orchestrator_addr,
+        &DenomUnit {
+            denom: REWARD_DENOM.to_string(),
+            exponent: 6,
+            aliases: vec![],
+        },
+        &[
+            RewardsDistributionByToken {
+                denom: DenomUnit {
+                    denom: STAKE_DENOM.to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+                weight: Uint64::from(50_000u64),
+            },
+            RewardsDistributionByToken {
+                denom: DenomUnit {
+                    denom: empty_denom.to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+                weight: Uint64::from(50_000u64),
+            },
+        ],
+    );
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+    add_rewards_hook(&mut app, &orchestrator_addr, empty_denom, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 3_000);
+
+    let set_policy = |app: &mut App, policy: EmptyPoolPolicy| {
+        app.execute_contract(
+            owner_address.clone(),
+            rewards_contract.clone(),
+            &super::msg::ExecuteMsg::UpdateConfig {
+                staking_orchestrator_addr: None,
+                reward_tokens: None,
+                rewards_distribution: None,
+                empty_pool_policy: Some(policy),
+            },
+            &[],
+        ).unwrap();
+    };
+    let distribute = |app: &mut App| {
+        let res = app.execute_contract(
+            owner_address.clone(),
+            rewards_contract.clone(),
+            &DistributeRewards {},
+            &[coin(1_000, REWARD_DENOM)],
+        ).unwrap();
+        let attribute = |key: &str| res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone());
+        (attribute("empty_pool_policy"), attribute("empty_pools"))
+    };
+    let applied = |policy: &str, empty_pools: &str| (Some(policy.to_string()), Some(empty_pools.to_string()));
+    let pool_total_rewards = |app: &App, denom: &str| {
+        let pool_state: PoolStateResponse = app.wrap().query_wasm_smart(
+            rewards_contract.clone(),
+            &QueryMsg::PoolState {
+                denom: denom.to_string(),
+                block_height: None,
+            },
+        ).unwrap();
+        pool_state.rewards.get(REWARD_DENOM).map(|rewards| rewards.total_rewards).unwrap_or_default()
+    };
+
+    // the empty pool share is queued for its future stakers
+    assert_eq!(distribute(&mut app), applied("queue", empty_denom));
+    assert_eq!(pool_total_rewards(&app, STAKE_DENOM), Uint128::new(500));
+    assert_eq!(pool_total_rewards(&app, empty_denom), Uint128::new(500));
+    let remainders: RemaindersResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::Remainders {},
+    ).unwrap();
+    assert!(remainders.remainders.contains(&PoolRemainders {
+        denom: empty_denom.to_string(),
+        remainders: vec![coin(500, REWARD_DENOM)],
+    }));
+    next_block(&mut app);
+
+    // the empty pool share goes to the pools with stakers
+    set_policy(&mut app, EmptyPoolPolicy::Redistribute);
+    assert_eq!(distribute(&mut app), applied("redistribute", empty_denom));
+    assert_eq!(pool_total_rewards(&app, STAKE_DENOM), Uint128::new(1_500));
+    assert_eq!(pool_total_rewards(&app, empty_denom), Uint128::new(500));
+    next_block(&mut app);
+
+    // the empty pool share is sent back to the distributor
+    set_policy(&mut app, EmptyPoolPolicy::ReturnToSender);
+    assert_eq!(distribute(&mut app), applied("return_to_sender", empty_denom));
+    assert_eq!(pool_total_rewards(&app, STAKE_DENOM), Uint128::new(2_000));
+    assert_eq!(pool_total_rewards(&app, empty_denom), Uint128::new(500));
+    let balance = app.wrap().query_balance(owner_address.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(500));
+
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(2_000));
+    next_block(&mut app);
+
+    // no policy applies once every pool has stakers
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_500);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, empty_denom, 100);
+    assert_eq!(distribute(&mut app), (None, None));
+    next_block(&mut app);
+
+    // without stakers anywhere there is nothing to redistribute to, so the rewards are queued
+    for (staker, denom) in [(&staker_a, STAKE_DENOM), (&staker_b, empty_denom)] {
+        let staking_contract: symphony_interfaces::orchestrator::StakingContractByDenomResponse = app.wrap().query_wasm_smart(
+            orchestrator_addr.clone(),
+            &symphony_interfaces::orchestrator::QueryMsg::StakingContractByDenom {
+                denom: denom.to_string(),
+            },
+        ).unwrap();
+        app.execute_contract(
+            staker.clone(),
+            Addr::unchecked(staking_contract.registered_contract.address),
+            &symphony_interfaces::staking::ExecuteMsg::Unstake { amount: Uint128::new(100), owner: None },
+            &[],
+        ).unwrap();
+    }
+    set_policy(&mut app, EmptyPoolPolicy::Redistribute);
+    assert_eq!(distribute(&mut app), applied("queue", &format!("{},{}", STAKE_DENOM, empty_denom)));
+    assert_eq!(pool_total_rewards(&app, STAKE_DENOM), Uint128::new(1_000));
+}
+
+#[test]
+pub fn update_config_should_add_and_sunset_pools() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+    let new_denom = "ustable";
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    create_staking_contract(&mut app, &orchestrator_addr, new_denom);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 2_000);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    let update_distribution = |app: &mut App, denom: &str| {
+        app.execute_contract(
+            owner_address.clone(),
+            rewards_contract.clone(),
+            &super::msg::ExecuteMsg::UpdateConfig {
+                staking_orchestrator_addr: None,
+                reward_tokens: None,
+                rewards_distribution: Some(vec![RewardsDistributionByToken {
+                    denom: DenomUnit {
+                        denom: denom.to_string(),
+                        exponent: 6,
+                        aliases: vec![],
+                    },
+                    weight: Uint64::from(100_000u64),
+                }]),
+                empty_pool_policy: None,
+            },
+            &[],
+        )
+    };
+
+    // pools can only be added for denoms with a registered staking contract
+    let err = update_distribution(&mut app, "uunknown").unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::UnregisteredPoolDenom { denom: "uunknown".to_string() }.to_string()
+    );
+
+    // nor for contracts that would not checkpoint their stakers
+    let err = update_distribution(&mut app, new_denom).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        ContractError::UnhookedPoolDenom { denom: new_denom.to_string() }.to_string()
+    );
+
+    add_rewards_hook(&mut app, &orchestrator_addr, new_denom, &rewards_contract);
+    update_distribution(&mut app, new_denom).unwrap();
+
+    let pool_states: ListPoolStatesResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::AllPoolStates {},
+    ).unwrap();
+    let active = pool_states.pool_states
+        .iter()
+        .map(|pool_state| (pool_state.denom.denom.as_str(), pool_state.active))
+        .collect::<Vec<_>>();
+    assert_eq!(active, vec![(new_denom, true), (STAKE_DENOM, false)]);
+
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, new_denom, 100);
+    next_block(&mut app);
+
+    // the new pool can be distributed to right away
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000, REWARD_DENOM)],
+    ).unwrap();
+
+    // rewards accrued in the sunset pool stay claimable
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    app.execute_contract(staker_b.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+
+    let balance = app.wrap().query_balance(staker_a, REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1_000));
+    let balance = app.wrap().query_balance(staker_b, REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1_000));
+}
+
+#[test]
+pub fn simulate_claim_should_match_claim() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_000);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    let simulate_claim = |app: &App| -> SimulateClaimResponse {
+        app.wrap().query_wasm_smart(
+            rewards_contract.clone(),
+            &QueryMsg::SimulateClaim { address: staker_a.to_string() },
+        ).unwrap()
+    };
+
+    let simulation = simulate_claim(&app);
+    assert_eq!(simulation, SimulateClaimResponse {
+        address: staker_a.to_string(),
+        rewards_by_pool: vec![PoolClaim {
+            denom: STAKE_DENOM.to_string(),
+            rewards: vec![coin(1_000, REWARD_DENOM)],
+        }],
+        total_rewards: vec![coin(1_000, REWARD_DENOM)],
+        can_claim: true,
+        reason: None,
+    });
+
+    app.execute_contract(owner_address.clone(), rewards_contract.clone(), &super::msg::ExecuteMsg::Pause {}, &[]).unwrap();
+    let simulation = simulate_claim(&app);
+    assert!(!simulation.can_claim);
+    assert_eq!(simulation.reason, Some(ContractError::ContractPaused {}.to_string()));
+    app.execute_contract(owner_address.clone(), rewards_contract.clone(), &super::msg::ExecuteMsg::Unpause {}, &[]).unwrap();
+
+    // the contract cannot pay more than it holds
+    app.init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &rewards_contract, vec![coin(999, REWARD_DENOM)])
+    }).unwrap();
+    let simulation = simulate_claim(&app);
+    let expected_error = ContractError::InsufficientRewardsBalance { denom: REWARD_DENOM.to_string() };
+    assert!(!simulation.can_claim);
+    assert_eq!(simulation.reason, Some(expected_error.to_string()));
+    let err = app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), expected_error.to_string());
+
+    mint_native(&mut app, rewards_contract.as_str(), REWARD_DENOM, 1);
+    assert!(simulate_claim(&app).can_claim);
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    let balance = app.wrap().query_balance(staker_a.clone(), REWARD_DENOM).unwrap();
+    assert_eq!(balance.amount, Uint128::new(1_000));
+
+    let simulation = simulate_claim(&app);
+    assert!(simulation.total_rewards.is_empty());
+    assert_eq!(simulation.reason, Some(ContractError::NoRewardsToClaim {}.to_string()));
+}
+
+#[test]
+pub fn insolvent_contract_should_refuse_distributions_and_claims() {
+    let mut app = mock_app();
+    let owner_address = app.api().addr_make(OWNER);
+    let staker_a = app.api().addr_make(STAKERA);
+    let staker_b = app.api().addr_make(STAKERB);
+
+    let orchestrator_addr = instantiate_orchestrator(&mut app, STAKE_DENOM);
+    let rewards_contract = instantiate_single_pool_rewards(&mut app, &orchestrator_addr);
+    add_rewards_hook(&mut app, &orchestrator_addr, STAKE_DENOM, &rewards_contract);
+
+    stake_some_tokens(&mut app, &staker_a, &orchestrator_addr, STAKE_DENOM, 100);
+    stake_some_tokens(&mut app, &staker_b, &orchestrator_addr, STAKE_DENOM, 100);
+    next_block(&mut app);
+
+    mint_native(&mut app, owner_address.as_str(), REWARD_DENOM, 1_100);
+    app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(1_000, REWARD_DENOM)],
+    ).unwrap();
+    next_block(&mut app);
+
+    let solvency: SolvencyResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::Solvency {},
+    ).unwrap();
+    assert_eq!(solvency.solvency, vec![DenomSolvency {
+        denom: REWARD_DENOM.to_string(),
+        liabilities: Uint128::new(1_000),
+        balance: Uint128::new(1_000),
+        surplus: Uint128::zero(),
+        deficit: Uint128::zero(),
+    }]);
+
+    // the contract loses funds it owes
+    app.init_modules(|router, _, storage| {
+        router.bank.init_balance(storage, &rewards_contract, vec![coin(600, REWARD_DENOM)])
+    }).unwrap();
+
+    let solvency: SolvencyResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::Solvency {},
+    ).unwrap();
+    assert_eq!(solvency.solvency[0].deficit, Uint128::new(400));
+
+    let expected_error = ContractError::Insolvent { denom: REWARD_DENOM.to_string() };
+    let err = app.execute_contract(
+        owner_address.clone(),
+        rewards_contract.clone(),
+        &DistributeRewards {},
+        &[coin(100, REWARD_DENOM)],
+    ).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), expected_error.to_string());
+
+    // the first claimer could be paid, but would leave nothing for the second
+    let err = app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), expected_error.to_string());
+
+    mint_native(&mut app, rewards_contract.as_str(), REWARD_DENOM, 400);
+    app.execute_contract(staker_a.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+    app.execute_contract(staker_b.clone(), rewards_contract.clone(), &ClaimRewards {}, &[]).unwrap();
+
+    let solvency: SolvencyResponse = app.wrap().query_wasm_smart(
+        rewards_contract.clone(),
+        &QueryMsg::Solvency {},
+    ).unwrap();
+    assert!(solvency.solvency[0].liabilities.is_zero());
+    assert!(solvency.solvency[0].balance.is_zero());
+}
+
+#[test]
+pub fn migrate_should_upgrade_v0_1_state() {
+    let app = &mut mock_app();
+    let owner = app.api().addr_make(OWNER);
+    let staker = app.api().addr_make(STAKERA);
+    let orchestrator_addr = instantiate_orchestrator(app, STAKE_DENOM);
+
+    let legacy_code_id = app.store_code(legacy_rewards_contract());
+    let rewards_addr = app.instantiate_contract(
+        legacy_code_id,
+        owner.clone(),
+        &LegacyInstantiateMsg {
+            orchestrator: orchestrator_addr,
+            staker: staker.clone(),
+        },
+        &[],
+        "legacy rewards",
+        Some(owner.to_string()),
+    ).unwrap();
+    next_block(app);
+    let credited_height = app.block_info().height;
+    app.execute_contract(owner.clone(), rewards_addr.clone(), &Empty {}, &[]).unwrap();
+    mint_native(app, rewards_addr.as_str(), REWARD_DENOM, 80);
+    next_block(app);
+
+    let rewards_code_id = app.store_code(staking_rewards_contract());
+    app.migrate_contract(owner, rewards_addr.clone(), &MigrateMsg {}, rewards_code_id).unwrap();
+
+    let config: ConfigResponse = app.wrap().query_wasm_smart(rewards_addr.clone(), &QueryMsg::Config {}).unwrap();
+    assert_eq!(config.reward_tokens.iter().map(|token| token.denom.as_str()).collect::<Vec<_>>(), vec![REWARD_DENOM]);
+    assert_eq!(config.empty_pool_policy, EmptyPoolPolicy::Queue);
+
+    // snapshot history is migrated too
+    let pool_state: PoolStateResponse = app.wrap().query_wasm_smart(rewards_addr.clone(), &QueryMsg::PoolState {
+        denom: STAKE_DENOM.to_string(),
+        block_height: Some(Uint64::new(credited_height)),
+    }).unwrap();
+    assert_eq!(pool_state.rewards, BTreeMap::from([(REWARD_DENOM.to_string(), PoolRewards {
+        total_rewards: Uint128::new(50),
+        ..PoolRewards::default()
+    })]));
+    assert!(pool_state.active);
+
+    let user_state: UserStateResponse = app.wrap().query_wasm_smart(rewards_addr.clone(), &QueryMsg::UserState {
+        address: staker.to_string(),
+        block_height: Some(Uint64::new(credited_height)),
+    }).unwrap();
+    assert_eq!(user_state.reward_debt, vec![coin(50, REWARD_DENOM)]);
+
+    // rewards credited before the migration stay claimable
+    app.execute_contract(staker.clone(), rewards_addr.clone(), &ClaimRewards {}, &[]).unwrap();
+    assert_eq!(app.wrap().query_balance(&staker, REWARD_DENOM).unwrap().amount, Uint128::new(80));
+}
@@ -0,0 +1,42 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+use cw_ownable::cw_ownable_execute;
+use cw_utils::Duration;
+
+/// Queries are the ones of `crate::staking::QueryMsg`, the CW20 address standing for the denom.
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: Option<String>,
+    pub token_address: String,
+    pub unbonding_period: Option<Duration>,
+    /// Contracts notified of stake changes from the start, e.g. the rewards contract.
+    pub hooks: Option<Vec<String>>,
+    /// DAO the contract serves as voting module for.
+    pub dao: Option<String>,
+}
+
+#[cw_ownable_execute]
+#[cw_serde]
+pub enum ExecuteMsg {
+    UpdateConfig {
+        unbonding_period: Option<Duration>,
+    },
+    /// Updates the settings other than the unbonding period. Fields not set are left unchanged.
+    UpdateSettings {
+        dao: Option<String>,
+    },
+    /// Tokens sent with a `ReceiveMsg`.
+    Receive(Cw20ReceiveMsg),
+    Unstake { amount: Uint128 },
+    Claim {},
+    /// Sends every token not owed to stakers to `recipient`.
+    SweepSurplus { recipient: String },
+    AddHook { addr: String },
+    RemoveHook { addr: String },
+}
+
+#[cw_serde]
+pub enum ReceiveMsg {
+    Stake {},
+}
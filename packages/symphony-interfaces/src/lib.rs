@@ -1,2 +1,3 @@
 pub mod staking;
-pub mod orchestrator;
\ No newline at end of file
+pub mod orchestrator;
+pub mod cw20_staking;
\ No newline at end of file
@@ -1,13 +1,46 @@
-use crate::staking::{StakedBalanceAtHeightResponse, StakerBalanceResponse};
+use crate::staking::{ConfigResponse, StakedBalanceAtHeightResponse, StakerBalanceResponse, TotalStakedAtHeightResponse};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::DenomUnit;
+use cosmwasm_std::{DenomUnit, Timestamp};
 use cw_ownable::cw_ownable_query;
 use std::collections::HashMap;
 
+/// Token a staking contract is created for.
+#[cw_serde]
+pub enum StakingToken {
+    Native(DenomUnit),
+    /// Address of the CW20 contract, which is also its denom in the registry.
+    Cw20 { address: String },
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum TokenKind {
+    #[default]
+    Native,
+    Cw20,
+}
+
+#[cw_serde]
+pub enum ContractStatus {
+    Active,
+    /// Replaced by another contract for the same denom. Stakers can still unstake and claim.
+    Deprecated,
+    /// Deregistered without a replacement.
+    Closed,
+}
+
 #[cw_serde]
 pub struct RegisteredContract {
     pub address: String,
+    /// The denom of a CW20 token is its contract address.
     pub token: DenomUnit,
+    pub kind: TokenKind,
+    pub registered_at: Timestamp,
+    pub status: ContractStatus,
+    /// Unknown for contracts registered before code IDs were tracked.
+    pub code_id: Option<u64>,
+    /// cw2 version, unknown for contracts without one.
+    pub version: Option<String>,
 }
 
 #[cw_ownable_query]
@@ -22,6 +55,17 @@ pub enum QueryMsg {
 
     #[returns(ListStakersByDenomResponse)]
     ListStakersByDenom { denom: String, start_after: Option<String>, limit: Option<u32> },
+
+    #[returns(RewardsContractResponse)]
+    RewardsContract {},
+
+    /// Registered contracts by denom, with their current config and total stake.
+    #[returns(ListStakingContractsResponse)]
+    ListStakingContracts { start_after: Option<String>, limit: Option<u32> },
+
+    /// Contracts replaced or deregistered for `denom`.
+    #[returns(RetiredStakingContractsResponse)]
+    RetiredStakingContracts { denom: String },
 }
 
 #[cw_serde]
@@ -39,4 +83,42 @@ pub struct AllTokensStakedBalanceAtHeightResponse {
 pub struct ListStakersByDenomResponse {
     pub denom: String,
     pub stakers: Vec<StakerBalanceResponse>,
-}
\ No newline at end of file
+}
+
+#[cw_serde]
+pub struct RewardsContractResponse {
+    pub address: Option<String>,
+}
+
+#[cw_serde]
+pub struct RetiredStakingContractsResponse {
+    pub denom: String,
+    pub contracts: Vec<RegisteredContract>,
+}
+
+#[cw_serde]
+pub struct StakingContractResponse {
+    pub denom: String,
+    pub registered_contract: RegisteredContract,
+    pub config: ConfigResponse,
+    pub total_staked: TotalStakedAtHeightResponse,
+}
+
+#[cw_serde]
+pub struct ListStakingContractsResponse {
+    pub contracts: Vec<StakingContractResponse>,
+}
+
+/// Query the rewards contract must answer for the orchestrator.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum RewardsQueryMsg {
+    /// Whether `denom` is part of the rewards distribution.
+    #[returns(IsRewardedResponse)]
+    IsRewarded { denom: String },
+}
+
+#[cw_serde]
+pub struct IsRewardedResponse {
+    pub rewarded: bool,
+}
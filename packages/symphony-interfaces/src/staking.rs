@@ -1,14 +1,71 @@
+use crate::orchestrator::TokenKind;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{DenomUnit, Uint128};
-use cw_controllers::ClaimsResponse;
+use cosmwasm_std::{to_json_binary, Addr, Coin, CosmosMsg, Decimal, DenomUnit, StdResult, Uint128, Uint64, WasmMsg};
+use cw2::ContractVersion;
+use cw_controllers::{ClaimsResponse, HooksResponse};
 use cw_ownable::{cw_ownable_execute, cw_ownable_query};
-use cw_utils::Duration;
+use cw_utils::{Duration, Expiration};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: Option<String>,
     pub denom_unit: DenomUnit,
     pub unbonding_period: Option<Duration>,
+    /// Contracts notified of stake changes from the start, e.g. the rewards contract.
+    pub hooks: Option<Vec<String>>,
+    /// DAO the contract serves as voting module for.
+    pub dao: Option<String>,
+}
+
+/// Which pending claims `Rebond` takes tokens from first.
+#[cw_serde]
+#[derive(Default)]
+pub enum RebondOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+/// Stake locked for `duration` weighs `multiplier` times its amount.
+#[cw_serde]
+pub struct LockTier {
+    pub duration: Duration,
+    pub multiplier: Decimal,
+}
+
+/// Validator receiving `weight` parts of the delegated stake.
+#[cw_serde]
+pub struct ValidatorWeight {
+    pub address: String,
+    pub weight: Uint64,
+}
+
+/// Delegates the staked tokens to chain validators instead of keeping them idle.
+#[cw_serde]
+pub struct DelegationConfig {
+    pub validators: Vec<ValidatorWeight>,
+    /// Chain unbonding time in seconds, the minimum wait of every claim.
+    pub unbonding_time: u64,
+    /// Receives the harvested staking rewards.
+    pub rewards_contract: String,
+}
+
+/// Staking action an operator can take for the address that approved it.
+#[cw_serde]
+pub enum Permission {
+    Unstake,
+    Claim,
+    Rebond,
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Permission::Unstake => write!(f, "unstake"),
+            Permission::Claim => write!(f, "claim"),
+            Permission::Rebond => write!(f, "rebond"),
+        }
+    }
 }
 
 #[cw_ownable_query]
@@ -34,6 +91,56 @@ pub enum QueryMsg {
 
     #[returns(ListStakersResponse)]
     ListStakers { start_after: Option<String>, limit: Option<u32> },
+
+    #[returns(HooksResponse)]
+    Hooks {},
+
+    #[returns(LockTiersResponse)]
+    LockTiers {},
+
+    /// Locked positions of an address that have not expired yet.
+    #[returns(PositionsResponse)]
+    Positions { address: String },
+
+    /// DAO DAO voting module query.
+    #[returns(VotingPowerAtHeightResponse)]
+    VotingPowerAtHeight {
+        address: String,
+        height: Option<u64>,
+    },
+
+    /// DAO DAO voting module query.
+    #[returns(TotalPowerAtHeightResponse)]
+    TotalPowerAtHeight {
+        height: Option<u64>,
+    },
+
+    /// DAO DAO voting module query, fails if no DAO is bound.
+    #[returns(cosmwasm_std::Addr)]
+    Dao {},
+
+    /// DAO DAO voting module query.
+    #[returns(InfoResponse)]
+    Info {},
+
+    /// Slashes applied between the two heights, both included.
+    #[returns(SlashEventsResponse)]
+    SlashEvents {
+        start_height: Option<u64>,
+        end_height: Option<u64>,
+    },
+
+    /// Compares the internal ledger of staked and unbonding tokens with the tokens held.
+    #[returns(ReconcileResponse)]
+    Reconcile {},
+
+    /// Unexpired operator approvals granted by `owner`.
+    #[returns(ApprovalsResponse)]
+    Approvals {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_ownable_execute]
@@ -42,15 +149,71 @@ pub enum ExecuteMsg {
     UpdateConfig {
         unbonding_period: Option<Duration>,
     },
+    /// Updates the settings other than the unbonding period. Fields not set are left unchanged.
+    UpdateSettings {
+        rebond_order: Option<RebondOrder>,
+        /// Existing positions keep their terms.
+        lock_tiers: Option<Vec<LockTier>>,
+        dao: Option<String>,
+        /// Delegation cannot be turned off once enabled.
+        delegation: Option<DelegationConfig>,
+    },
     Stake {},
-    Unstake { amount: Uint128 },
-    Claim {},
+    /// Splits the sent tokens between the recipients, which must add up to exactly the
+    /// amount sent.
+    StakeFor { recipients: Vec<(String, Uint128)> },
+    /// Stakes the sent tokens in a new position that cannot be unstaked until the lock
+    /// tier with this `duration` has elapsed.
+    StakeLocked { duration: Duration },
+    /// Acts for `owner` when set, who must have approved the sender to unstake. The
+    /// tokens always go to the owner.
+    Unstake { amount: Uint128, owner: Option<String> },
+    /// Acts for `owner` when set, who must have approved the sender to claim.
+    Claim { owner: Option<String> },
+    /// Moves tokens from pending claims back into the staked balance. Acts for `owner`
+    /// when set, who must have approved the sender to rebond.
+    Rebond { amount: Uint128, owner: Option<String> },
+    /// Burns `ratio` of every staked balance and pending claim, sending the tokens to
+    /// `recipient`. Refused while delegating.
+    Slash { ratio: Decimal, recipient: String },
+    /// Withdraws the chain staking rewards and forwards them to the rewards contract, with
+    /// the ones the chain withdrew itself when delegations changed.
+    Harvest {},
+    /// Undelegates the tokens unstaked since the last undelegation once the chain unbonding
+    /// entries allow another one. Unstaking does it too when due. Claims are released as if
+    /// the batch were undelegated when due, so it should be called then, as claims of a late
+    /// batch cannot be paid until its tokens are back.
+    ProcessUndelegations {},
+    /// Sends every token not owed to stakers to `recipient`.
+    SweepSurplus { recipient: String },
+    /// Lets `operator` take the `permissions` actions for the sender until `expires`,
+    /// replacing any previous approval. Never expires when not set.
+    Approve {
+        operator: String,
+        expires: Option<Expiration>,
+        permissions: Vec<Permission>,
+    },
+    Revoke { operator: String },
+    AddHook { addr: String },
+    RemoveHook { addr: String },
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
     pub staking_token: DenomUnit,
     pub unstaking_duration: Option<Duration>,
+    // the fields below default for contracts of the first release, which predate them
+    #[serde(default)]
+    pub rebond_order: RebondOrder,
+    #[serde(default)]
+    pub lock_tiers: Vec<LockTier>,
+    #[serde(default)]
+    pub dao: Option<Addr>,
+    #[serde(default)]
+    pub delegation: Option<DelegationConfig>,
+    /// Kind of the staked token, native for the contracts that predate it.
+    #[serde(default)]
+    pub token_kind: TokenKind,
 }
 
 #[cw_serde]
@@ -74,4 +237,139 @@ pub struct StakedBalanceAtHeightResponse {
 pub struct StakerBalanceResponse {
     pub address: String,
     pub balance: Uint128,
-}
\ No newline at end of file
+}
+
+#[cw_serde]
+pub struct LockTiersResponse {
+    pub lock_tiers: Vec<LockTier>,
+}
+
+#[cw_serde]
+pub struct PositionResponse {
+    pub id: u64,
+    pub amount: Uint128,
+    pub lock_duration: Duration,
+    pub unlock_at: Expiration,
+    pub multiplier: Decimal,
+}
+
+#[cw_serde]
+pub struct PositionsResponse {
+    pub positions: Vec<PositionResponse>,
+}
+
+#[cw_serde]
+pub struct VotingPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct TotalPowerAtHeightResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct InfoResponse {
+    pub info: ContractVersion,
+}
+
+#[cw_serde]
+pub struct SlashEventResponse {
+    pub height: u64,
+    pub ratio: Decimal,
+    pub amount: Uint128,
+    pub recipient: String,
+    pub exchange_rate: Decimal,
+}
+
+#[cw_serde]
+pub struct SlashEventsResponse {
+    pub slash_events: Vec<SlashEventResponse>,
+}
+
+#[cw_serde]
+pub struct ReconcileResponse {
+    pub denom: String,
+    /// Staked and unbonding tokens owed to stakers.
+    pub ledger: Uint128,
+    pub balance: Uint128,
+    pub delegated: Uint128,
+    pub surplus: Uint128,
+    pub deficit: Uint128,
+    /// Balances in any other denom not owed as rewards, all of them surplus.
+    pub other_balances: Vec<Coin>,
+    /// Staking rewards the chain withdrew into the contract, owed to the rewards contract
+    /// until the next harvest. Part of `balance` when in the staking denom.
+    pub unforwarded_rewards: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct ApprovalResponse {
+    pub operator: String,
+    pub expires: Expiration,
+    pub permissions: Vec<Permission>,
+}
+
+#[cw_serde]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<ApprovalResponse>,
+}
+
+/// Sent to every registered hook contract when a staked balance changes.
+#[cw_serde]
+pub struct StakeChangedHookMsg {
+    pub addr: String,
+    pub old_balance: Uint128,
+    pub new_balance: Uint128,
+    pub denom: String,
+}
+
+impl StakeChangedHookMsg {
+    pub fn into_json_binary(self) -> StdResult<cosmwasm_std::Binary> {
+        to_json_binary(&StakeChangedExecuteMsg::StakeChangedHook(self))
+    }
+
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_json_binary()?,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// Sent to every registered hook contract when the stake is slashed. Every staked balance
+/// shrinks by the same ratio without a stake changed hook.
+#[cw_serde]
+pub struct SlashHookMsg {
+    pub denom: String,
+    /// Tokens per staked unit after the slash.
+    pub exchange_rate: Decimal,
+}
+
+impl SlashHookMsg {
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: to_json_binary(&StakeChangedExecuteMsg::SlashHook(self))?,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// Execute variant the rewards contract must accept to receive harvested rewards.
+#[cw_serde]
+pub enum RewardsExecuteMsg {
+    DistributeRewards {},
+}
+
+/// Execute variants hook receivers must accept.
+#[cw_serde]
+pub enum StakeChangedExecuteMsg {
+    StakeChangedHook(StakeChangedHookMsg),
+    SlashHook(SlashHookMsg),
+}
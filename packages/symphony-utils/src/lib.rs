@@ -1 +1,2 @@
-pub mod duration;
\ No newline at end of file
+pub mod duration;
+pub mod migrate;
\ No newline at end of file
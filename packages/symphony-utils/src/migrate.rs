@@ -0,0 +1,68 @@
+use cosmwasm_std::{Env, StdError, Storage};
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MigrationError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error("Cannot migrate {found} to {expected}")]
+    WrongContract { expected: String, found: String },
+
+    #[error("Cannot downgrade from {stored} to {new}")]
+    Downgrade { stored: String, new: String },
+
+    #[error("Invalid contract version {version}")]
+    InvalidVersion { version: String },
+}
+
+/// State transformer run when migrating from a version older than the one it is registered for.
+pub type Migration<E> = (&'static str, fn(&mut dyn Storage, &Env) -> Result<(), E>);
+
+fn parse_version(version: &str) -> Result<Version, MigrationError> {
+    version.parse().map_err(|_| MigrationError::InvalidVersion { version: version.to_string() })
+}
+
+/// Checks the stored cw2 info belongs to `contract_name` and is not newer than `version`,
+/// returning the stored version.
+pub fn assert_upgrade(storage: &dyn Storage, contract_name: &str, version: &str) -> Result<Version, MigrationError> {
+    let stored = get_contract_version(storage)?;
+    if stored.contract != contract_name {
+        return Err(MigrationError::WrongContract {
+            expected: contract_name.to_string(),
+            found: stored.contract,
+        });
+    }
+
+    let stored_version = parse_version(&stored.version)?;
+    if stored_version > parse_version(version)? {
+        return Err(MigrationError::Downgrade { stored: stored.version, new: version.to_string() });
+    }
+
+    Ok(stored_version)
+}
+
+/// Runs, in order, the `migrations` registered for versions newer than the stored one, then
+/// stores `version`. Returns the version migrated from.
+pub fn migrate_contract<E: From<MigrationError>>(
+    storage: &mut dyn Storage,
+    env: &Env,
+    contract_name: &str,
+    version: &str,
+    migrations: &[Migration<E>],
+) -> Result<Version, E> {
+    let stored = assert_upgrade(storage, contract_name, version)?;
+    let target = parse_version(version)?;
+
+    for (migration_version, migration) in migrations {
+        let migration_version = parse_version(migration_version)?;
+        if stored < migration_version && migration_version <= target {
+            migration(storage, env)?;
+        }
+    }
+
+    set_contract_version(storage, contract_name, version).map_err(MigrationError::from)?;
+    Ok(stored)
+}